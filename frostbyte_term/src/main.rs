@@ -3,7 +3,7 @@ mod ui;
 use ui::UI;
 
 fn main() {
-    iced::application("frozen term", UI::update, UI::view)
+    iced::application(UI::title, UI::update, UI::view)
         .subscription(UI::subscription)
         .theme(|_| iced::Theme::Dark)
         .antialiasing(true)
@@ -1,11 +1,19 @@
 use std::{
     env,
+    fs::File,
+    io::BufWriter,
     ops::Deref,
     sync::{Arc, Mutex},
+    time::{SystemTime, UNIX_EPOCH},
 };
 
-use frozen_term::Terminal;
-use iced::{Element, Subscription, Task, futures::SinkExt, stream::channel};
+use frozen_term::{KeyAction, KeyBindings, RecordingOptions, Terminal};
+use iced::{
+    Element, Subscription, Task,
+    futures::SinkExt,
+    keyboard::{Key, Modifiers},
+    stream::channel,
+};
 use portable_pty::{Child, PtyPair, PtySize};
 use tokio::task::{JoinHandle, spawn_blocking};
 
@@ -16,6 +24,9 @@ pub enum Message {
     TerminalOutput(Vec<u8>),
 }
 
+/// Emitted by the Ctrl+Shift+R key binding.
+const TOGGLE_RECORDING: &str = "toggle-recording";
+
 pub struct UI {
     term: Terminal,
     child: Box<dyn Child + Send + Sync>,
@@ -63,7 +74,13 @@ impl UI {
 
         let writer = pty.master.take_writer().unwrap();
 
-        let term = Terminal::new(rows, cols, writer);
+        let key_bindings = KeyBindings::default().bind(
+            Key::Character("r".into()),
+            Modifiers::CTRL | Modifiers::SHIFT,
+            KeyAction::Emit(TOGGLE_RECORDING.to_string()),
+        );
+
+        let term = Terminal::new(rows, cols, writer).key_bindings(key_bindings);
 
         (
             Self {
@@ -77,8 +94,22 @@ impl UI {
         )
     }
 
+    pub fn title(&self) -> String {
+        if self.term.is_recording() {
+            "frozen term (recording)".to_string()
+        } else {
+            "frozen term".to_string()
+        }
+    }
+
     pub fn update(&mut self, message: Message) -> Task<Message> {
         match message {
+            Message::Terminal(frozen_term::Message::Action(KeyAction::Emit(action)))
+                if action == TOGGLE_RECORDING =>
+            {
+                self.toggle_recording();
+                Task::none()
+            }
             Message::Terminal(msg) => {
                 if let frozen_term::Message::Resize(size) = msg {
                     let pty_size = PtySize {
@@ -99,7 +130,36 @@ impl UI {
         }
     }
 
-    pub fn view(&self) -> Element<Message> {
+    /// Records the session to `frostbyte-<timestamp>.cast` in the working directory.
+    fn toggle_recording(&mut self) {
+        if self.term.is_recording() {
+            self.term.stop_recording();
+            return;
+        }
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let path = format!("frostbyte-{timestamp}.cast");
+
+        let file = match File::create(&path) {
+            Ok(file) => file,
+            Err(err) => {
+                println!("Could not create {path}: {err}");
+                return;
+            }
+        };
+
+        // recording writes on every output, so don't make each a syscall
+        let file = BufWriter::new(file);
+        match self.term.start_recording(file, RecordingOptions::default()) {
+            Ok(()) => println!("Recording to {path}"),
+            Err(err) => println!("Could not write to {path}: {err}"),
+        }
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
         self.term.view().map(Message::Terminal)
     }
 
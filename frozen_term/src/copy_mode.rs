@@ -0,0 +1,311 @@
+use iced::keyboard::{Key, Modifiers, key::Named};
+
+use crate::{
+    SearchPattern, Terminal,
+    selection::{self, GridPoint, Selection, SelectionMode, Unit},
+};
+
+/// What the widget has to do after a key press in copy mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CopyModeAction {
+    /// The cursor or the selection may have changed.
+    Update,
+    /// Copies the selection to the clipboard and leaves copy mode.
+    Yank,
+    Exit,
+}
+
+/// A vi-like mode for moving through the scrollback and selecting text with the keyboard.
+///
+/// The cursor moves independently of the cursor of the program, and is kept at the
+/// same line while new output arrives, since it uses stable row indices.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CopyMode {
+    pub cursor: GridPoint,
+    /// The selection mode and the position where `v`, `V` or `Ctrl+V` was pressed.
+    visual: Option<(SelectionMode, GridPoint)>,
+    /// Whether `g` was pressed, waiting for the second `g`.
+    pending_g: bool,
+    /// The query while it is typed after `/`.
+    pub search_input: Option<String>,
+    last_search: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CharClass {
+    Word,
+    Whitespace,
+    Other,
+}
+
+impl CopyMode {
+    pub fn new(cursor: GridPoint) -> Self {
+        Self {
+            cursor,
+            visual: None,
+            pending_g: false,
+            search_input: None,
+            last_search: None,
+        }
+    }
+
+    /// Handles a key press, `key` being the key with Shift applied.
+    pub fn handle_key(
+        &mut self,
+        term: &Terminal,
+        key: &Key,
+        modifiers: Modifiers,
+        word_chars: &str,
+    ) -> CopyModeAction {
+        if self.search_input.is_some() {
+            self.handle_search_key(term, key, modifiers);
+            return CopyModeAction::Update;
+        }
+
+        let pending_g = std::mem::take(&mut self.pending_g);
+        let rows = term.all_rows();
+        let last_col = term.size().cols.saturating_sub(1);
+
+        match key.as_ref() {
+            Key::Named(Named::Escape) if self.visual.is_some() => self.visual = None,
+            Key::Named(Named::Escape) => return CopyModeAction::Exit,
+            Key::Character("c") if modifiers.control() => return CopyModeAction::Exit,
+            Key::Character("v") if modifiers.control() => self.toggle_visual(SelectionMode::Block),
+            _ if modifiers.control() || modifiers.alt() || modifiers.logo() => {}
+            Key::Character("q") => return CopyModeAction::Exit,
+            Key::Character("y") | Key::Named(Named::Enter) if self.visual.is_some() => {
+                return CopyModeAction::Yank;
+            }
+            Key::Character("h") | Key::Named(Named::ArrowLeft) => {
+                self.cursor.col = self.cursor.col.saturating_sub(1);
+            }
+            Key::Character("l") | Key::Named(Named::ArrowRight) => {
+                self.cursor.col = (self.cursor.col + 1).min(last_col);
+            }
+            Key::Character("k") | Key::Named(Named::ArrowUp) => {
+                self.cursor.row = (self.cursor.row - 1).max(rows.start);
+            }
+            Key::Character("j") | Key::Named(Named::ArrowDown) => {
+                self.cursor.row = (self.cursor.row + 1).min(rows.end - 1);
+            }
+            Key::Character("0") | Key::Named(Named::Home) => self.cursor.col = 0,
+            Key::Character("$") | Key::Named(Named::End) => {
+                self.cursor.col = (0..=last_col)
+                    .rev()
+                    .find(|col| {
+                        let point = GridPoint {
+                            row: self.cursor.row,
+                            col: *col,
+                        };
+                        char_class(term, point, word_chars) != CharClass::Whitespace
+                    })
+                    .unwrap_or(0);
+            }
+            Key::Character("w") => self.word_forward(term, word_chars),
+            Key::Character("b") => self.word_backward(term, word_chars),
+            Key::Character("g") if pending_g => {
+                self.cursor = GridPoint {
+                    row: rows.start,
+                    col: 0,
+                };
+            }
+            Key::Character("g") => self.pending_g = true,
+            Key::Character("G") => {
+                self.cursor = GridPoint {
+                    row: rows.end - 1,
+                    col: 0,
+                };
+            }
+            Key::Character("v") => self.toggle_visual(SelectionMode::Cell),
+            Key::Character("V") => self.toggle_visual(SelectionMode::Line),
+            Key::Character("/") => self.search_input = Some(String::new()),
+            Key::Character("n") => self.search(term, true),
+            Key::Character("N") => self.search(term, false),
+            _ => {}
+        }
+
+        CopyModeAction::Update
+    }
+
+    /// The selection from where visual mode started to the cursor.
+    pub fn selection(&self, term: &wezterm_term::Terminal) -> Option<Selection> {
+        let (mode, anchor) = self.visual?;
+
+        // unlike mouse selections, the cell below the cursor is always included
+        let unit = |point: GridPoint| -> Unit {
+            match mode {
+                SelectionMode::Line => selection::line_at(term, point),
+                _ => (
+                    point,
+                    GridPoint {
+                        row: point.row,
+                        col: point.col + 1,
+                    },
+                ),
+            }
+        };
+
+        let mut selection = Selection::new(mode, unit(anchor));
+        selection.extend(unit(self.cursor));
+        Some(selection)
+    }
+
+    fn toggle_visual(&mut self, mode: SelectionMode) {
+        self.visual = match self.visual {
+            Some((current, _)) if current == mode => None,
+            // switching the mode keeps the start of the selection
+            Some((_, anchor)) => Some((mode, anchor)),
+            None => Some((mode, self.cursor)),
+        };
+    }
+
+    fn handle_search_key(&mut self, term: &Terminal, key: &Key, modifiers: Modifiers) {
+        let Some(input) = &mut self.search_input else {
+            return;
+        };
+
+        match key.as_ref() {
+            Key::Named(Named::Escape) => self.search_input = None,
+            Key::Named(Named::Enter) => {
+                self.last_search = self.search_input.take().filter(|query| !query.is_empty());
+                self.search(term, true);
+            }
+            Key::Named(Named::Backspace) => {
+                input.pop();
+            }
+            Key::Named(Named::Space) => input.push(' '),
+            Key::Character(text) if !modifiers.control() && !modifiers.logo() => {
+                input.push_str(text);
+            }
+            _ => {}
+        }
+    }
+
+    /// Moves the cursor to the next (or previous) match of the last search, wrapping around.
+    fn search(&mut self, term: &Terminal, forward: bool) {
+        let Some(query) = &self.last_search else {
+            return;
+        };
+
+        // like vim's smartcase, queries with uppercase letters are case-sensitive
+        let pattern = if query.chars().any(char::is_uppercase) {
+            SearchPattern::Text(query.clone())
+        } else {
+            SearchPattern::CaseInsensitive(query.clone())
+        };
+
+        // a query too long to search for has no matches
+        let matches = term.find(&pattern).unwrap_or_default();
+        let found = if forward {
+            matches
+                .iter()
+                .find(|found| found.start > self.cursor)
+                .or(matches.first())
+        } else {
+            matches
+                .iter()
+                .rev()
+                .find(|found| found.start < self.cursor)
+                .or(matches.last())
+        };
+
+        if let Some(found) = found {
+            self.cursor = found.start;
+        }
+    }
+
+    /// Moves to the start of the next word, like vim's `w`.
+    fn word_forward(&mut self, term: &Terminal, word_chars: &str) {
+        let class = |point| char_class(term, point, word_chars);
+        let start = self.cursor;
+        let mut point = start;
+
+        // skip the rest of the current word, which ends at the end of the row
+        while class(point) != CharClass::Whitespace && class(point) == class(start) {
+            match next_point(term, point) {
+                Some(next) if next.row == start.row => point = next,
+                Some(next) => {
+                    point = next;
+                    break;
+                }
+                None => return,
+            }
+        }
+
+        while class(point) == CharClass::Whitespace {
+            match next_point(term, point) {
+                Some(next) => point = next,
+                None => break,
+            }
+        }
+
+        self.cursor = point;
+    }
+
+    /// Moves to the start of the previous word, like vim's `b`.
+    fn word_backward(&mut self, term: &Terminal, word_chars: &str) {
+        let class = |point| char_class(term, point, word_chars);
+        let Some(mut point) = previous_point(term, self.cursor) else {
+            return;
+        };
+
+        while class(point) == CharClass::Whitespace {
+            match previous_point(term, point) {
+                Some(previous) => point = previous,
+                None => break,
+            }
+        }
+
+        let word = class(point);
+        while let Some(previous) = previous_point(term, point)
+            && previous.row == point.row
+            && class(previous) == word
+        {
+            point = previous;
+        }
+
+        self.cursor = point;
+    }
+}
+
+fn char_class(term: &Terminal, point: GridPoint, word_chars: &str) -> CharClass {
+    let Some(c) = term.cell(point).and_then(|cell| cell.str().chars().next()) else {
+        return CharClass::Whitespace;
+    };
+
+    if c.is_alphanumeric() || word_chars.contains(c) {
+        CharClass::Word
+    } else if c.is_whitespace() {
+        CharClass::Whitespace
+    } else {
+        CharClass::Other
+    }
+}
+
+fn next_point(term: &Terminal, point: GridPoint) -> Option<GridPoint> {
+    if point.col + 1 < term.size().cols {
+        Some(GridPoint {
+            col: point.col + 1,
+            ..point
+        })
+    } else {
+        (point.row + 1 < term.all_rows().end).then_some(GridPoint {
+            row: point.row + 1,
+            col: 0,
+        })
+    }
+}
+
+fn previous_point(term: &Terminal, point: GridPoint) -> Option<GridPoint> {
+    if point.col > 0 {
+        Some(GridPoint {
+            col: point.col - 1,
+            ..point
+        })
+    } else {
+        (point.row > term.all_rows().start).then_some(GridPoint {
+            row: point.row - 1,
+            col: term.size().cols.saturating_sub(1),
+        })
+    }
+}
@@ -0,0 +1,138 @@
+use std::time::Duration;
+
+use termwiz::escape::{Action, ControlCode, parser::Parser};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt},
+    time::Instant,
+};
+
+use crate::{Terminal, search::SearchPattern};
+
+#[derive(Debug, thiserror::Error)]
+pub enum ExpectError {
+    #[error("timed out waiting for the expected output")]
+    Timeout,
+    #[error("the output ended before the expected output appeared")]
+    Eof,
+    #[error("invalid pattern: {0}")]
+    Pattern(#[from] regex::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// Drives a [`Terminal`] like the classic `expect` tool.
+///
+/// The output of the program is read from `output` and fed into the terminal with
+/// [`Terminal::advance_bytes`], so this works with a pty as well as with in-memory
+/// streams in tests. Input is written to the writer the terminal was created with.
+pub struct Expect<R> {
+    terminal: Terminal,
+    output: R,
+    parser: Parser,
+    /// The printed text which wasn't consumed by a match yet.
+    pending: String,
+}
+
+impl<R: AsyncRead + Unpin> Expect<R> {
+    pub fn new(terminal: Terminal, output: R) -> Self {
+        Self {
+            terminal,
+            output,
+            parser: Parser::new(),
+            pending: String::new(),
+        }
+    }
+
+    pub fn terminal(&self) -> &Terminal {
+        &self.terminal
+    }
+
+    pub fn terminal_mut(&mut self) -> &mut Terminal {
+        &mut self.terminal
+    }
+
+    pub fn into_terminal(self) -> Terminal {
+        self.terminal
+    }
+
+    /// Waits until the new output matches the pattern and returns the matched text.
+    ///
+    /// Escape sequences are stripped from the output before matching. The output
+    /// up to the end of the match is consumed, so the next call only sees what came after it.
+    pub async fn expect_output(
+        &mut self,
+        pattern: &SearchPattern,
+        timeout: Duration,
+    ) -> Result<String, ExpectError> {
+        let regex = pattern.regex()?;
+
+        self.wait(timeout, |expect| {
+            let found = regex.find(&expect.pending)?;
+            let text = found.as_str().to_string();
+            expect.pending.drain(..found.end());
+            Some(text)
+        })
+        .await
+    }
+
+    /// Waits until the visible screen matches the pattern and returns the matched text.
+    ///
+    /// The lines of the screen are joined by newlines before matching.
+    pub async fn expect_screen(
+        &mut self,
+        pattern: &SearchPattern,
+        timeout: Duration,
+    ) -> Result<String, ExpectError> {
+        let regex = pattern.regex()?;
+
+        self.wait(timeout, |expect| {
+            let terminal = &expect.terminal;
+            let screen = terminal.lines(terminal.screen_rows()).join("\n");
+            regex.find(&screen).map(|found| found.as_str().to_string())
+        })
+        .await
+    }
+
+    /// Writes the input to the program, as if it was typed.
+    pub fn send(&mut self, input: impl AsRef<[u8]>) {
+        self.terminal.send_bytes(input);
+    }
+
+    async fn wait<T>(
+        &mut self,
+        timeout: Duration,
+        mut check: impl FnMut(&mut Self) -> Option<T>,
+    ) -> Result<T, ExpectError> {
+        let deadline = Instant::now() + timeout;
+        let mut buffer = [0; 4096];
+
+        loop {
+            if let Some(result) = check(self) {
+                return Ok(result);
+            }
+
+            let read = tokio::time::timeout_at(deadline, self.output.read(&mut buffer))
+                .await
+                .map_err(|_| ExpectError::Timeout)??;
+
+            if read == 0 {
+                return Err(ExpectError::Eof);
+            }
+
+            self.feed(&buffer[..read]);
+        }
+    }
+
+    fn feed(&mut self, bytes: &[u8]) {
+        self.terminal.advance_bytes(bytes);
+
+        let pending = &mut self.pending;
+        self.parser.parse(bytes, |action| match action {
+            Action::Print(c) => pending.push(c),
+            Action::PrintString(text) => pending.push_str(&text),
+            Action::Control(ControlCode::LineFeed) => pending.push('\n'),
+            Action::Control(ControlCode::HorizontalTab) => pending.push('\t'),
+            _ => {}
+        });
+    }
+}
@@ -0,0 +1,345 @@
+use std::{fmt::Write, ops::Range};
+
+use iced::Color;
+use termwiz::{
+    cell::{CellAttributes, Intensity, Underline},
+    color::ColorAttribute,
+};
+use wezterm_term::{StableRowIndex, color::ColorPalette};
+
+use crate::terminal::{CHAR_WIDTH, LINE_HEIGHT, get_color, to_color};
+
+/// The formats the terminal contents can be exported to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// Only the text, without any attributes.
+    PlainText,
+    /// Text with SGR escape sequences, e.g. for `cat`ing it in another terminal.
+    Ansi,
+    /// A standalone HTML document.
+    Html,
+    /// A standalone SVG image.
+    Svg,
+}
+
+/// The colors used for cells without an explicit color, matching the widget.
+const DEFAULT_FOREGROUND: Color = Color::WHITE;
+const DEFAULT_BACKGROUND: Color = Color::BLACK;
+/// The font size of SVG exports, in pixels.
+const SVG_FONT_SIZE: f32 = 14.0;
+
+/// A run of cells with the same attributes.
+struct Run {
+    col: usize,
+    width: usize,
+    text: String,
+    attrs: CellAttributes,
+}
+
+pub(crate) fn export(
+    term: &wezterm_term::Terminal,
+    rows: Range<StableRowIndex>,
+    format: ExportFormat,
+) -> String {
+    let screen = term.screen();
+    let lines: Vec<Vec<Run>> = screen
+        .lines_in_phys_range(screen.stable_range(&rows))
+        .iter()
+        .map(|line| {
+            let mut runs: Vec<Run> = Vec::new();
+
+            for cell in line.visible_cells() {
+                match runs.last_mut() {
+                    Some(run) if run.attrs == *cell.attrs() => {
+                        run.text.push_str(cell.str());
+                        run.width += cell.width();
+                    }
+                    _ => runs.push(Run {
+                        col: cell.cell_index(),
+                        width: cell.width(),
+                        text: cell.str().to_string(),
+                        attrs: cell.attrs().clone(),
+                    }),
+                }
+            }
+
+            trim_end(&mut runs);
+            runs
+        })
+        .collect();
+
+    let palette = term.palette();
+    match format {
+        ExportFormat::PlainText => plain_text(&lines),
+        ExportFormat::Ansi => ansi(&lines),
+        ExportFormat::Html => html(&lines, &palette, term.get_title()),
+        ExportFormat::Svg => svg(&lines, &palette, screen.physical_cols),
+    }
+}
+
+/// Removes trailing whitespace, unless it is visible because of its background.
+fn trim_end(runs: &mut Vec<Run>) {
+    while let Some(run) = runs.last_mut() {
+        if run.attrs.background() != ColorAttribute::Default || run.attrs.reverse() {
+            return;
+        }
+
+        let trimmed = run.text.trim_end_matches(' ').len();
+        run.width -= run.text.len() - trimmed;
+        run.text.truncate(trimmed);
+
+        if !run.text.is_empty() {
+            return;
+        }
+        runs.pop();
+    }
+}
+
+fn plain_text(lines: &[Vec<Run>]) -> String {
+    lines
+        .iter()
+        .map(|runs| runs.iter().map(|run| run.text.as_str()).collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn ansi(lines: &[Vec<Run>]) -> String {
+    let mut out = String::new();
+
+    for (index, runs) in lines.iter().enumerate() {
+        if index > 0 {
+            out.push_str("\r\n");
+        }
+
+        for run in runs {
+            write!(out, "\x1b[{}m{}", sgr(&run.attrs), run.text).unwrap();
+        }
+
+        if !runs.is_empty() {
+            out.push_str("\x1b[0m");
+        }
+    }
+
+    out
+}
+
+/// The SGR parameters setting exactly the given attributes.
+fn sgr(attrs: &CellAttributes) -> String {
+    let mut params = vec!["0".to_string()];
+
+    match attrs.intensity() {
+        Intensity::Normal => {}
+        Intensity::Bold => params.push("1".into()),
+        Intensity::Half => params.push("2".into()),
+    }
+    if attrs.italic() {
+        params.push("3".into());
+    }
+    match attrs.underline() {
+        Underline::None => {}
+        Underline::Double => params.push("21".into()),
+        _ => params.push("4".into()),
+    }
+    if attrs.reverse() {
+        params.push("7".into());
+    }
+    if attrs.invisible() {
+        params.push("8".into());
+    }
+    if attrs.strikethrough() {
+        params.push("9".into());
+    }
+    if let Some(color) = sgr_color(attrs.foreground(), 30, 90, 38) {
+        params.push(color);
+    }
+    if let Some(color) = sgr_color(attrs.background(), 40, 100, 48) {
+        params.push(color);
+    }
+
+    params.join(";")
+}
+
+fn sgr_color(color: ColorAttribute, normal: u8, bright: u8, extended: u8) -> Option<String> {
+    match color {
+        ColorAttribute::Default => None,
+        ColorAttribute::PaletteIndex(index @ 0..=7) => Some((normal + index).to_string()),
+        ColorAttribute::PaletteIndex(index @ 8..=15) => Some((bright + index - 8).to_string()),
+        ColorAttribute::PaletteIndex(index) => Some(format!("{extended};5;{index}")),
+        ColorAttribute::TrueColorWithPaletteFallback(color, _)
+        | ColorAttribute::TrueColorWithDefaultFallback(color) => {
+            let [r, g, b, _] = to_color(color).into_rgba8();
+            Some(format!("{extended};2;{r};{g};{b}"))
+        }
+    }
+}
+
+/// The colors of the text and its background, with reverse video applied.
+fn colors(attrs: &CellAttributes, palette: &ColorPalette) -> (Color, Option<Color>) {
+    let foreground = get_color(attrs.foreground(), palette).unwrap_or(DEFAULT_FOREGROUND);
+    let background = get_color(attrs.background(), palette);
+
+    if attrs.reverse() {
+        (background.unwrap_or(DEFAULT_BACKGROUND), Some(foreground))
+    } else {
+        (foreground, background)
+    }
+}
+
+fn hex(color: Color) -> String {
+    let [r, g, b, _] = color.into_rgba8();
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// The CSS (and SVG presentation attribute) styles of the text, besides its colors.
+fn text_styles(attrs: &CellAttributes) -> Vec<(&'static str, &'static str)> {
+    let mut styles = Vec::new();
+
+    match attrs.intensity() {
+        Intensity::Normal => {}
+        Intensity::Bold => styles.push(("font-weight", "bold")),
+        Intensity::Half => styles.push(("opacity", "0.5")),
+    }
+    if attrs.italic() {
+        styles.push(("font-style", "italic"));
+    }
+    match (attrs.underline() != Underline::None, attrs.strikethrough()) {
+        (true, true) => styles.push(("text-decoration", "underline line-through")),
+        (true, false) => styles.push(("text-decoration", "underline")),
+        (false, true) => styles.push(("text-decoration", "line-through")),
+        (false, false) => {}
+    }
+    if attrs.invisible() {
+        styles.push(("visibility", "hidden"));
+    }
+
+    styles
+}
+
+fn html(lines: &[Vec<Run>], palette: &ColorPalette, title: &str) -> String {
+    let mut out = String::new();
+
+    writeln!(out, "<!DOCTYPE html>").unwrap();
+    writeln!(out, "<html>").unwrap();
+    writeln!(out, "<head>").unwrap();
+    writeln!(out, "<meta charset=\"utf-8\">").unwrap();
+    writeln!(out, "<title>{}</title>", escape(title)).unwrap();
+    writeln!(out, "</head>").unwrap();
+    writeln!(out, "<body>").unwrap();
+    write!(
+        out,
+        "<pre style=\"font-family: monospace; color: {}; background-color: {}; padding: 0.5em;\">",
+        hex(DEFAULT_FOREGROUND),
+        hex(DEFAULT_BACKGROUND)
+    )
+    .unwrap();
+
+    for (index, runs) in lines.iter().enumerate() {
+        if index > 0 {
+            out.push('\n');
+        }
+
+        for run in runs {
+            let (foreground, background) = colors(&run.attrs, palette);
+            let mut style = String::new();
+
+            if foreground != DEFAULT_FOREGROUND {
+                write!(style, "color: {};", hex(foreground)).unwrap();
+            }
+            if let Some(background) = background {
+                write!(style, "background-color: {};", hex(background)).unwrap();
+            }
+            for (property, value) in text_styles(&run.attrs) {
+                write!(style, "{property}: {value};").unwrap();
+            }
+
+            let text = escape(&run.text);
+            let text = match run.attrs.hyperlink() {
+                Some(link) => format!("<a href=\"{}\">{text}</a>", escape(link.uri())),
+                None => text,
+            };
+
+            if style.is_empty() {
+                out.push_str(&text);
+            } else {
+                write!(out, "<span style=\"{style}\">{text}</span>").unwrap();
+            }
+        }
+    }
+
+    writeln!(out, "</pre>").unwrap();
+    writeln!(out, "</body>").unwrap();
+    writeln!(out, "</html>").unwrap();
+
+    out
+}
+
+fn svg(lines: &[Vec<Run>], palette: &ColorPalette, cols: usize) -> String {
+    let char_width = SVG_FONT_SIZE * CHAR_WIDTH;
+    let line_height = SVG_FONT_SIZE * LINE_HEIGHT;
+    let width = cols as f32 * char_width;
+    let height = lines.len() as f32 * line_height;
+
+    let mut out = String::new();
+
+    writeln!(
+        out,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+         viewBox=\"0 0 {width} {height}\" font-family=\"monospace\" font-size=\"{SVG_FONT_SIZE}\">"
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "<rect width=\"100%\" height=\"100%\" fill=\"{}\"/>",
+        hex(DEFAULT_BACKGROUND)
+    )
+    .unwrap();
+
+    for (row, runs) in lines.iter().enumerate() {
+        let y = row as f32 * line_height;
+
+        for run in runs {
+            let (foreground, background) = colors(&run.attrs, palette);
+            let x = run.col as f32 * char_width;
+            let run_width = run.width as f32 * char_width;
+
+            if let Some(background) = background {
+                writeln!(
+                    out,
+                    "<rect x=\"{x}\" y=\"{y}\" width=\"{run_width}\" height=\"{line_height}\" fill=\"{}\"/>",
+                    hex(background)
+                )
+                .unwrap();
+            }
+
+            if run.text.trim().is_empty() {
+                continue;
+            }
+
+            let mut attributes = format!("fill=\"{}\"", hex(foreground));
+            for (property, value) in text_styles(&run.attrs) {
+                write!(attributes, " {property}=\"{value}\"").unwrap();
+            }
+
+            // stretch the text over its cells, so it stays aligned to the grid with any font
+            writeln!(
+                out,
+                "<text x=\"{x}\" y=\"{}\" textLength=\"{run_width}\" lengthAdjust=\"spacingAndGlyphs\" \
+                 xml:space=\"preserve\" {attributes}>{}</text>",
+                y + SVG_FONT_SIZE,
+                escape(&run.text)
+            )
+            .unwrap();
+        }
+    }
+
+    writeln!(out, "</svg>").unwrap();
+
+    out
+}
@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+
+use iced::{Point, Rectangle, Size, advanced::image::Handle};
+use termwiz::image::{ImageData, ImageDataType};
+use wezterm_term::Line;
+
+/// An image (or the visible part of it) to be drawn on top of the cell grid.
+#[derive(Debug, Clone)]
+pub(crate) struct ImagePlacement {
+    pub handle: Handle,
+    /// Where the whole image would be drawn, relative to the widget.
+    pub bounds: Rectangle,
+    /// The cells showing the image, relative to the widget.
+    pub clip: Rectangle,
+    /// Images with a negative z-index are drawn below the text.
+    pub z_index: i32,
+}
+
+/// Keeps the image handles alive between layouts, so iced doesn't have to
+/// upload the image again every time the screen changes.
+#[derive(Debug, Default)]
+pub(crate) struct ImageCache {
+    handles: HashMap<[u8; 32], Handle>,
+}
+
+impl ImageCache {
+    fn handle(&mut self, image: &ImageData) -> Option<Handle> {
+        let hash = image.hash();
+
+        if let Some(handle) = self.handles.get(&hash) {
+            return Some(handle.clone());
+        }
+
+        let handle = match &*image.data() {
+            ImageDataType::EncodedFile(data) => Handle::from_bytes(data.clone()),
+            ImageDataType::Rgba8 {
+                data,
+                width,
+                height,
+                ..
+            } => Handle::from_rgba(*width, *height, data.clone()),
+            // animations only show their first frame
+            ImageDataType::AnimRgba8 {
+                width,
+                height,
+                frames,
+                ..
+            } => Handle::from_rgba(*width, *height, frames.first()?.clone()),
+            // leases are only used with a blob manager, which frozen_term never sets up
+            ImageDataType::EncodedLease(_) => return None,
+        };
+
+        self.handles.insert(hash, handle.clone());
+        Some(handle)
+    }
+}
+
+/// Collects the images attached to the cells of the visible lines.
+///
+/// Every cell carries the fragment of the image it shows. Fragments of the same
+/// image are merged into a single placement, clipped to the cells showing it.
+pub(crate) fn placements(
+    lines: &[Line],
+    cache: &mut ImageCache,
+    char_width: f32,
+    line_height: f32,
+) -> Vec<ImagePlacement> {
+    let mut placements: Vec<(ImagePlacement, [u8; 32])> = Vec::new();
+
+    for (row, line) in lines.iter().enumerate() {
+        for cell in line.visible_cells() {
+            let Some(images) = cell.attrs().images() else {
+                continue;
+            };
+
+            let cell_bounds = Rectangle::new(
+                Point::new(
+                    cell.cell_index() as f32 * char_width,
+                    row as f32 * line_height,
+                ),
+                Size::new(cell.width() as f32 * char_width, line_height),
+            );
+
+            for image in images {
+                let top_left = image.top_left();
+                let bottom_right = image.bottom_right();
+                let fraction =
+                    Size::new(*bottom_right.x - *top_left.x, *bottom_right.y - *top_left.y);
+
+                if fraction.width <= 0.0 || fraction.height <= 0.0 {
+                    continue;
+                }
+
+                let size = Size::new(
+                    cell_bounds.width / fraction.width,
+                    cell_bounds.height / fraction.height,
+                );
+                let bounds = Rectangle::new(
+                    Point::new(
+                        cell_bounds.x - *top_left.x * size.width,
+                        cell_bounds.y - *top_left.y * size.height,
+                    ),
+                    size,
+                );
+
+                let hash = image.image_data().hash();
+
+                // the positions are calculated per cell, so allow for some rounding errors
+                let existing = placements.iter_mut().find(|(placement, other)| {
+                    *other == hash
+                        && placement.z_index == image.z_index()
+                        && (placement.bounds.x - bounds.x).abs() < 1.0
+                        && (placement.bounds.y - bounds.y).abs() < 1.0
+                });
+
+                if let Some((placement, _)) = existing {
+                    placement.clip = placement.clip.union(&cell_bounds);
+                } else if let Some(handle) = cache.handle(image.image_data()) {
+                    placements.push((
+                        ImagePlacement {
+                            handle,
+                            bounds,
+                            clip: cell_bounds,
+                            z_index: image.z_index(),
+                        },
+                        hash,
+                    ));
+                }
+            }
+        }
+    }
+
+    // forget images which scrolled out of view
+    cache
+        .handles
+        .retain(|hash, _| placements.iter().any(|(_, other)| other == hash));
+
+    placements
+        .into_iter()
+        .map(|(placement, _)| placement)
+        .collect()
+}
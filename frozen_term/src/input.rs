@@ -0,0 +1,209 @@
+use iced::keyboard::{Key, Modifiers, key::Named};
+
+/// What the Alt (Meta) modifier does to a character key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AltEncoding {
+    /// Sends ESC followed by the character, like xterm's `metaSendsEscape`.
+    #[default]
+    EscapePrefix,
+    /// Sets the 8th bit of the character.
+    ///
+    /// Only ASCII characters can be encoded like this, all other characters
+    /// fall back to the ESC prefix.
+    EighthBit,
+}
+
+/// What the Backspace key sends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BackspaceEncoding {
+    /// Sends `^?` (DEL, `0x7f`). Ctrl+Backspace sends `^H`.
+    #[default]
+    Del,
+    /// Sends `^H` (BS, `0x08`). Ctrl+Backspace sends `^?`.
+    CtrlH,
+}
+
+/// What the Delete key sends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DeleteEncoding {
+    /// Sends the VT220 escape sequence `ESC [ 3 ~`.
+    #[default]
+    EscapeSequence,
+    /// Sends `^?` (DEL, `0x7f`).
+    Del,
+}
+
+/// Controls how some keys are encoded before being written to the terminal.
+///
+/// The defaults match what modern hosts expect. Older remote hosts might need
+/// a different setup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct KeyEncoding {
+    pub alt: AltEncoding,
+    pub backspace: BackspaceEncoding,
+    pub delete: DeleteEncoding,
+}
+
+impl KeyEncoding {
+    /// Returns the bytes for the key press, if it is encoded differently from
+    /// what wezterm_term would send.
+    pub(crate) fn encode(&self, key: &Key, modifiers: Modifiers) -> Option<Vec<u8>> {
+        match key {
+            Key::Named(Named::Backspace)
+                if self.backspace == BackspaceEncoding::CtrlH
+                    && !modifiers.shift()
+                    && !modifiers.logo() =>
+            {
+                let byte = if modifiers.control() { 0x7f } else { 0x08 };
+
+                if modifiers.alt() {
+                    Some(vec![0x1b, byte])
+                } else {
+                    Some(vec![byte])
+                }
+            }
+            Key::Named(Named::Delete)
+                if self.delete == DeleteEncoding::Del && modifiers.is_empty() =>
+            {
+                Some(vec![0x7f])
+            }
+            Key::Character(c)
+                if self.alt == AltEncoding::EighthBit
+                    && modifiers.alt()
+                    && !modifiers.control()
+                    && !modifiers.logo() =>
+            {
+                let mut chars = c.chars();
+
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) if c.is_ascii() => Some(vec![c as u8 | 0x80]),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum KeyNotationError {
+    #[error("unknown key <{0}>")]
+    UnknownKey(String),
+    #[error("unclosed `<` at byte {0}, write `<lt>` for a literal `<`")]
+    Unclosed(usize),
+}
+
+/// Parses keys in vim's notation, e.g. `ls -l<CR>` or `<C-c>`.
+///
+/// Special keys and modifiers go between angle brackets: `C-` is Ctrl, `S-` is Shift,
+/// `A-` or `M-` is Alt and `D-` is Super. Everything else is typed as is.
+pub(crate) fn parse_keys(notation: &str) -> Result<Vec<(Key, Modifiers)>, KeyNotationError> {
+    let mut keys = Vec::new();
+    let mut rest = notation;
+
+    while let Some(c) = rest.chars().next() {
+        if c != '<' {
+            keys.push((Key::Character(c.to_string().into()), Modifiers::empty()));
+            rest = &rest[c.len_utf8()..];
+            continue;
+        }
+
+        let start = notation.len() - rest.len();
+        let end = rest.find('>').ok_or(KeyNotationError::Unclosed(start))?;
+        keys.push(parse_key(&rest[1..end])?);
+        rest = &rest[end + 1..];
+    }
+
+    Ok(keys)
+}
+
+/// Parses the part between the angle brackets, e.g. `C-S-Tab`.
+fn parse_key(notation: &str) -> Result<(Key, Modifiers), KeyNotationError> {
+    let unknown = || KeyNotationError::UnknownKey(notation.to_string());
+    let mut modifiers = Modifiers::empty();
+    let mut name = notation;
+
+    // the last part is the key itself, which may be a `-`
+    while let Some((modifier, rest)) = name.split_once('-')
+        && !rest.is_empty()
+    {
+        modifiers |= match modifier.to_ascii_uppercase().as_str() {
+            "C" => Modifiers::CTRL,
+            "S" => Modifiers::SHIFT,
+            "A" | "M" => Modifiers::ALT,
+            "D" => Modifiers::LOGO,
+            _ => return Err(unknown()),
+        };
+        name = rest;
+    }
+
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Ok((Key::Character(c.to_string().into()), modifiers));
+    }
+
+    let named = match name.to_ascii_lowercase().as_str() {
+        "lt" => return Ok((Key::Character("<".into()), modifiers)),
+        "cr" | "enter" | "return" => Named::Enter,
+        "esc" => Named::Escape,
+        "tab" => Named::Tab,
+        "bs" | "backspace" => Named::Backspace,
+        "del" | "delete" => Named::Delete,
+        "space" => Named::Space,
+        "up" => Named::ArrowUp,
+        "down" => Named::ArrowDown,
+        "left" => Named::ArrowLeft,
+        "right" => Named::ArrowRight,
+        function => {
+            let number: u8 = function
+                .strip_prefix('f')
+                .and_then(|number| number.parse().ok())
+                .ok_or_else(unknown)?;
+            function_key(number).ok_or_else(unknown)?
+        }
+    };
+
+    Ok((Key::Named(named), modifiers))
+}
+
+fn function_key(number: u8) -> Option<Named> {
+    const KEYS: [Named; 35] = [
+        Named::F1,
+        Named::F2,
+        Named::F3,
+        Named::F4,
+        Named::F5,
+        Named::F6,
+        Named::F7,
+        Named::F8,
+        Named::F9,
+        Named::F10,
+        Named::F11,
+        Named::F12,
+        Named::F13,
+        Named::F14,
+        Named::F15,
+        Named::F16,
+        Named::F17,
+        Named::F18,
+        Named::F19,
+        Named::F20,
+        Named::F21,
+        Named::F22,
+        Named::F23,
+        Named::F24,
+        Named::F25,
+        Named::F26,
+        Named::F27,
+        Named::F28,
+        Named::F29,
+        Named::F30,
+        Named::F31,
+        Named::F32,
+        Named::F33,
+        Named::F34,
+        Named::F35,
+    ];
+
+    KEYS.get(usize::from(number).checked_sub(1)?).copied()
+}
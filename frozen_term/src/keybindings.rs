@@ -0,0 +1,193 @@
+use iced::keyboard::{Key, Modifiers, key::Named};
+
+/// An action that can be bound to a key combination.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyAction {
+    /// Copies the current selection to the clipboard.
+    Copy,
+    /// Pastes the clipboard contents into the terminal.
+    Paste,
+    /// Scrolls the viewport by the given amount of lines.
+    ///
+    /// Negative values scroll up into the scrollback.
+    ScrollLines(isize),
+    /// Scrolls the viewport by the given amount of pages.
+    ///
+    /// Negative values scroll up into the scrollback.
+    ScrollPages(isize),
+    /// Scrolls to the oldest line of the scrollback.
+    ScrollToTop,
+    /// Scrolls back to the live screen.
+    ScrollToBottom,
+    IncreaseFontSize,
+    DecreaseFontSize,
+    ResetFontSize,
+    /// Clears the scrollback, keeping the visible screen.
+    ClearScrollback,
+    /// Scrolls the visible screen into the scrollback, keeping the cursor line.
+    ClearScreen,
+    /// Resets modes and text attributes (DECSTR), keeping the contents.
+    SoftReset,
+    /// Fully resets the terminal (RIS), clearing the screen and scrollback.
+    HardReset,
+    /// Enters the vi-like copy mode, for selecting text with the keyboard.
+    ///
+    /// Move with `hjkl`, `w`, `b`, `0`, `$`, `gg` and `G`, search with `/`, `n` and `N`,
+    /// select with `v`, `V` or `Ctrl+V` and copy with `y`. `q` or `Esc` leaves it.
+    CopyMode,
+    /// Writes the given bytes to the terminal input, as if they were typed.
+    SendBytes(Vec<u8>),
+    /// Does nothing inside the terminal.
+    ///
+    /// Match on [`Message::Action`](crate::Message::Action) in your own
+    /// update function to react to it.
+    Emit(String),
+}
+
+/// A single key combination bound to a [`KeyAction`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyBinding {
+    pub key: Key,
+    pub modifiers: Modifiers,
+    pub action: KeyAction,
+}
+
+/// The table of [`KeyBinding`]s checked before a key press is sent to the terminal.
+///
+/// The [`Default`] implementation contains the usual terminal shortcuts.
+/// Use [`KeyBindings::empty`] to start from scratch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyBindings {
+    bindings: Vec<KeyBinding>,
+}
+
+impl KeyBindings {
+    /// Creates a table without any bindings.
+    pub fn empty() -> Self {
+        Self {
+            bindings: Vec::new(),
+        }
+    }
+
+    /// Binds the key combination to the action, replacing any previous binding for it.
+    pub fn bind(mut self, key: Key, modifiers: Modifiers, action: KeyAction) -> Self {
+        let key = normalize(key);
+        self.bindings
+            .retain(|binding| binding.key != key || binding.modifiers != modifiers);
+        self.bindings.push(KeyBinding {
+            key,
+            modifiers,
+            action,
+        });
+        self
+    }
+
+    /// Removes the binding for the key combination, if there is one.
+    pub fn unbind(mut self, key: Key, modifiers: Modifiers) -> Self {
+        let key = normalize(key);
+        self.bindings
+            .retain(|binding| binding.key != key || binding.modifiers != modifiers);
+        self
+    }
+
+    /// Returns the action bound to the key combination.
+    ///
+    /// `key` is expected to be the key without modifiers applied, as reported
+    /// by [`iced::keyboard::Event::KeyPressed`].
+    pub fn find(&self, key: &Key, modifiers: Modifiers) -> Option<&KeyAction> {
+        let key = normalize(key.clone());
+        self.bindings
+            .iter()
+            .find(|binding| binding.key == key && binding.modifiers == modifiers)
+            .map(|binding| &binding.action)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &KeyBinding> {
+        self.bindings.iter()
+    }
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        let ctrl_shift = Modifiers::CTRL | Modifiers::SHIFT;
+
+        Self::empty()
+            .bind(Key::Character("c".into()), ctrl_shift, KeyAction::Copy)
+            .bind(Key::Character("v".into()), ctrl_shift, KeyAction::Paste)
+            .bind(
+                Key::Named(Named::Insert),
+                Modifiers::SHIFT,
+                KeyAction::Paste,
+            )
+            .bind(
+                Key::Named(Named::PageUp),
+                Modifiers::SHIFT,
+                KeyAction::ScrollPages(-1),
+            )
+            .bind(
+                Key::Named(Named::PageDown),
+                Modifiers::SHIFT,
+                KeyAction::ScrollPages(1),
+            )
+            .bind(
+                Key::Named(Named::ArrowUp),
+                ctrl_shift,
+                KeyAction::ScrollLines(-1),
+            )
+            .bind(
+                Key::Named(Named::ArrowDown),
+                ctrl_shift,
+                KeyAction::ScrollLines(1),
+            )
+            .bind(
+                Key::Named(Named::Home),
+                Modifiers::SHIFT,
+                KeyAction::ScrollToTop,
+            )
+            .bind(
+                Key::Named(Named::End),
+                Modifiers::SHIFT,
+                KeyAction::ScrollToBottom,
+            )
+            .bind(
+                Key::Character("=".into()),
+                Modifiers::CTRL,
+                KeyAction::IncreaseFontSize,
+            )
+            // Ctrl++ arrives as Ctrl+Shift+= on layouts where + is shifted, like US
+            .bind(
+                Key::Character("=".into()),
+                ctrl_shift,
+                KeyAction::IncreaseFontSize,
+            )
+            .bind(
+                Key::Character("+".into()),
+                Modifiers::CTRL,
+                KeyAction::IncreaseFontSize,
+            )
+            .bind(
+                Key::Character("-".into()),
+                Modifiers::CTRL,
+                KeyAction::DecreaseFontSize,
+            )
+            .bind(
+                Key::Character("0".into()),
+                Modifiers::CTRL,
+                KeyAction::ResetFontSize,
+            )
+            .bind(
+                Key::Character("k".into()),
+                ctrl_shift,
+                KeyAction::ClearScrollback,
+            )
+            .bind(Key::Character("x".into()), ctrl_shift, KeyAction::CopyMode)
+    }
+}
+
+/// Character keys are compared case-insensitively, since shift is part of the modifiers.
+fn normalize(key: Key) -> Key {
+    match key {
+        Key::Character(c) => Key::Character(c.to_lowercase().into()),
+        key => key,
+    }
+}
@@ -1,5 +1,57 @@
+mod copy_mode;
+mod expect;
+mod export;
+mod images;
+mod input;
+mod keybindings;
+mod links;
+mod log_viewer;
+mod osc52;
+mod player;
+mod recording;
+mod search;
+mod selection;
+mod smart_selection;
 mod terminal;
+#[cfg(feature = "testing")]
+mod testing;
 
+pub use expect::Expect;
+pub use expect::ExpectError;
+pub use export::ExportFormat;
+pub use input::AltEncoding;
+pub use input::BackspaceEncoding;
+pub use input::DeleteEncoding;
+pub use input::KeyEncoding;
+pub use input::KeyNotationError;
+pub use keybindings::KeyAction;
+pub use keybindings::KeyBinding;
+pub use keybindings::KeyBindings;
+pub use links::Link;
+pub use links::LinkRule;
+pub use log_viewer::LogViewer;
+pub use osc52::ClipboardPolicy;
+pub use osc52::ClipboardRequest;
+pub use player::CastError;
+pub use player::Player;
+pub use player::PlayerMessage;
+pub use recording::RecordingOptions;
+pub use search::SearchMatch;
+pub use search::SearchPattern;
+pub use selection::GridPoint;
+pub use smart_selection::SmartSelectionRule;
+pub use terminal::Cell;
+pub use terminal::CellAttributes;
+pub use terminal::CursorPosition;
+pub use terminal::Id;
 pub use terminal::Message;
+pub use terminal::StableRowIndex;
 pub use terminal::Terminal;
 pub use terminal::TerminalSize;
+pub use terminal::focus;
+pub use terminal::is_focused;
+pub use terminal::unfocus;
+#[cfg(feature = "testing")]
+pub use testing::CaptureWriter;
+#[cfg(feature = "testing")]
+pub use testing::TestTerminal;
@@ -0,0 +1,206 @@
+use std::{ops::Range, path::PathBuf};
+
+use regex::Regex;
+use wezterm_term::StableRowIndex;
+
+use crate::selection::{self, GridPoint};
+
+/// The target of a link that was clicked in the terminal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Link {
+    /// An OSC 8 hyperlink or a detected URL.
+    Uri(String),
+    /// A detected file path, optionally with a position, e.g. from compiler output.
+    File {
+        path: PathBuf,
+        line: Option<usize>,
+        column: Option<usize>,
+    },
+}
+
+pub(crate) const URL_REGEX: &str =
+    r#"\b(?:https?|ftp|file)://[^\s<>"'()\[\]{}]*[^\s<>"'()\[\]{}.,;:!?]"#;
+const FILE_POSITION_REGEX: &str =
+    r"(?P<path>(?:~|\.{1,2})?/?(?:[\w.\-]+/)*[\w\-]+\.\w+):(?P<line>\d+)(?::(?P<col>\d+))?";
+/// Absolute paths have to start at a word boundary, so `read/write` or dates aren't links.
+pub(crate) const PATH_REGEX: &str =
+    r"(?P<path>\B(?:~|\.{1,2})?/[\w.\-]+(?:/[\w.\-]+)*|(?:[\w.\-]+/)+[\w\-]+\.\w+)";
+
+#[derive(Debug, Clone)]
+enum LinkKind {
+    Uri,
+    File,
+}
+
+/// A rule for detecting links in the text of the terminal.
+///
+/// Rules are checked in order, the first rule matching at the mouse position wins.
+/// Hyperlinks set by the application through OSC 8 always take precedence.
+#[derive(Debug, Clone)]
+pub struct LinkRule {
+    regex: Regex,
+    kind: LinkKind,
+}
+
+impl LinkRule {
+    /// Creates a rule producing a [`Link::Uri`].
+    ///
+    /// The `uri` capture group is used as the URI if present, otherwise the whole match.
+    pub fn uri(regex: &str) -> Result<Self, regex::Error> {
+        Ok(Self {
+            regex: Regex::new(regex)?,
+            kind: LinkKind::Uri,
+        })
+    }
+
+    /// Creates a rule producing a [`Link::File`].
+    ///
+    /// The regex needs a `path` capture group, and can have `line` and `col`
+    /// capture groups for the position inside the file.
+    pub fn file(regex: &str) -> Result<Self, regex::Error> {
+        Ok(Self {
+            regex: Regex::new(regex)?,
+            kind: LinkKind::File,
+        })
+    }
+
+    /// The default rules, detecting URLs, `path:line:col` references and paths.
+    pub fn defaults() -> Vec<Self> {
+        vec![
+            Self::uri(URL_REGEX).unwrap(),
+            Self::file(FILE_POSITION_REGEX).unwrap(),
+            Self::file(PATH_REGEX).unwrap(),
+        ]
+    }
+
+    fn link(&self, captures: &regex::Captures) -> Link {
+        match self.kind {
+            LinkKind::Uri => {
+                let uri = captures.name("uri").unwrap_or(captures.get(0).unwrap());
+                Link::Uri(uri.as_str().to_string())
+            }
+            LinkKind::File => {
+                let number = |name| {
+                    captures
+                        .name(name)
+                        .and_then(|number| number.as_str().parse().ok())
+                };
+
+                Link::File {
+                    path: PathBuf::from(
+                        captures
+                            .name("path")
+                            .unwrap_or(captures.get(0).unwrap())
+                            .as_str(),
+                    ),
+                    line: number("line"),
+                    column: number("col"),
+                }
+            }
+        }
+    }
+}
+
+/// A link below the mouse cursor, together with all the cells it covers.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct HoveredLink {
+    pub link: Link,
+    pub cells: Vec<(StableRowIndex, Range<usize>)>,
+}
+
+/// Finds the link at the given cell, either an OSC 8 hyperlink or one detected by the rules.
+pub(crate) fn link_at(
+    term: &wezterm_term::Terminal,
+    rules: &[LinkRule],
+    phys_range: Range<usize>,
+    point: GridPoint,
+) -> Option<HoveredLink> {
+    hyperlink_at(term, phys_range, point).or_else(|| detected_link_at(term, rules, point))
+}
+
+/// Finds the OSC 8 hyperlink at the given cell.
+///
+/// All cells in `phys_range` pointing to the same hyperlink are part of the
+/// result, so links spanning several lines are highlighted as a whole.
+fn hyperlink_at(
+    term: &wezterm_term::Terminal,
+    phys_range: Range<usize>,
+    point: GridPoint,
+) -> Option<HoveredLink> {
+    let screen = term.screen();
+    let phys = screen.stable_row_to_phys(point.row)?;
+    let line = screen.lines_in_phys_range(phys..phys + 1).pop()?;
+    let hyperlink = line.get_cell(point.col)?.attrs().hyperlink()?.clone();
+
+    let mut cells: Vec<(StableRowIndex, Range<usize>)> = Vec::new();
+
+    for (line, phys) in screen
+        .lines_in_phys_range(phys_range.clone())
+        .iter()
+        .zip(phys_range)
+    {
+        let row = screen.phys_to_stable_row_index(phys);
+
+        for cell in line.visible_cells() {
+            if cell.attrs().hyperlink() != Some(&hyperlink) {
+                continue;
+            }
+
+            push_cell(
+                &mut cells,
+                row,
+                cell.cell_index()..cell.cell_index() + cell.width(),
+            );
+        }
+    }
+
+    Some(HoveredLink {
+        link: Link::Uri(hyperlink.uri().to_string()),
+        cells,
+    })
+}
+
+/// Runs the rules over the logical line (following wrapped lines) containing the cell.
+fn detected_link_at(
+    term: &wezterm_term::Terminal,
+    rules: &[LinkRule],
+    point: GridPoint,
+) -> Option<HoveredLink> {
+    if rules.is_empty() {
+        return None;
+    }
+
+    let (line, hovered) = selection::logical_line_at(term, point)?;
+
+    rules.iter().find_map(|rule| {
+        let captures = rule.regex.captures_iter(&line.text).find(|captures| {
+            let whole = captures.get(0).unwrap();
+            whole.start() <= hovered && hovered < whole.end()
+        })?;
+        let whole = captures.get(0).unwrap();
+
+        let mut cells = Vec::new();
+        for (row, cols) in line.cells(whole.range()) {
+            push_cell(&mut cells, row, cols);
+        }
+
+        Some(HoveredLink {
+            link: rule.link(&captures),
+            cells,
+        })
+    })
+}
+
+/// Adds the cells to the list, merging them with the previous cells if they are adjacent.
+fn push_cell(
+    cells: &mut Vec<(StableRowIndex, Range<usize>)>,
+    row: StableRowIndex,
+    cols: Range<usize>,
+) {
+    match cells.last_mut() {
+        Some((last_row, range)) if *last_row == row && range.end == cols.start => {
+            range.end = cols.end;
+        }
+        _ => cells.push((row, cols)),
+    }
+}
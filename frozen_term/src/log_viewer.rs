@@ -0,0 +1,100 @@
+use iced::{Element, Task};
+
+use crate::{ClipboardPolicy, KeyAction, Message, Terminal, terminal::Id};
+
+/// How many lines a [`LogViewer`] keeps by default.
+const DEFAULT_LOG_SCROLLBACK_SIZE: usize = 100_000;
+
+/// A read-only terminal for displaying colored output, like build logs.
+///
+/// There is no program behind it: output is added with [`LogViewer::advance_bytes`],
+/// key presses are never sent anywhere, and the cursor is hidden. The view can still
+/// be scrolled, selected and zoomed, and the lines reflow when the widget is resized.
+///
+/// The log isn't unlimited: once it holds 100,000 lines, the oldest lines are dropped
+/// as new ones are appended. Use [`LogViewer::with_scrollback_size`] for another limit.
+pub struct LogViewer {
+    terminal: Terminal,
+    /// Whether the last appended byte was a carriage return, so a `\r\n` split
+    /// between two calls isn't turned into `\r\r\n`.
+    after_carriage_return: bool,
+}
+
+impl Default for LogViewer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LogViewer {
+    pub fn new() -> Self {
+        Self::with_scrollback_size(DEFAULT_LOG_SCROLLBACK_SIZE)
+    }
+
+    /// Creates a viewer keeping at most `lines` lines.
+    pub fn with_scrollback_size(lines: usize) -> Self {
+        Self {
+            terminal: Terminal::new(24, 80, Box::new(std::io::sink()))
+                .scrollback_size(lines)
+                .clipboard_policy(ClipboardPolicy::Deny)
+                .read_only(),
+            after_carriage_return: false,
+        }
+    }
+
+    pub fn id(mut self, id: impl Into<Id>) -> Self {
+        self.terminal = self.terminal.id(id);
+        self
+    }
+
+    /// Appends output to the log.
+    ///
+    /// Lone `\n` line endings are treated as `\r\n`, as most logs aren't written for a terminal.
+    pub fn advance_bytes<B: AsRef<[u8]>>(&mut self, bytes: B) {
+        let bytes = bytes.as_ref();
+        let mut converted = Vec::with_capacity(bytes.len());
+
+        for &byte in bytes {
+            if byte == b'\n' && !self.after_carriage_return {
+                converted.push(b'\r');
+            }
+            converted.push(byte);
+            self.after_carriage_return = byte == b'\r';
+        }
+
+        self.terminal.advance_bytes(converted);
+    }
+
+    pub fn update(&mut self, message: Message) -> Task<Message> {
+        match message {
+            // there is no program to send input to
+            Message::KeyPress { .. }
+            | Message::Paste(_)
+            | Message::Action(KeyAction::SendBytes(_)) => Task::none(),
+            message => self.terminal.update(message),
+        }
+    }
+
+    pub fn view<'a, Theme, Renderer>(&'a self) -> Element<'a, Message, Theme, Renderer>
+    where
+        Renderer: iced::advanced::text::Renderer<Font = iced::Font> + 'static,
+        Renderer: iced::advanced::image::Renderer<Handle = iced::advanced::image::Handle>,
+        Theme: iced::widget::text::Catalog + 'static,
+        Theme: iced::widget::container::Catalog,
+        <Theme as iced::widget::text::Catalog>::Class<'static>:
+            From<iced::widget::text::StyleFn<'static, Theme>>,
+        <Theme as iced::widget::container::Catalog>::Class<'static>:
+            From<iced::widget::container::StyleFn<'static, Theme>>,
+    {
+        self.terminal.view()
+    }
+
+    /// The terminal holding the log, e.g. for searching or exporting it.
+    pub fn terminal(&self) -> &Terminal {
+        &self.terminal
+    }
+
+    pub fn terminal_mut(&mut self) -> &mut Terminal {
+        &mut self.terminal
+    }
+}
@@ -0,0 +1,176 @@
+use std::sync::{Arc, Mutex};
+
+use iced::{
+    Task,
+    advanced::{Shell, clipboard::Kind},
+};
+use termwiz::escape::{Action, OperatingSystemCommand, osc::Selection, parser::Parser};
+use wezterm_term::{Clipboard, ClipboardSelection};
+
+use crate::{KeyAction, Message};
+
+/// What programs may do with the clipboard through OSC 52.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ClipboardPolicy {
+    /// Ignores all OSC 52 sequences.
+    Deny,
+    /// Lets programs copy to the clipboard, e.g. from tmux or neovim over ssh.
+    #[default]
+    AllowWrite,
+    /// Also lets programs read the clipboard.
+    ///
+    /// Any program, including `cat`ing an untrusted file, can then read whatever
+    /// was copied last, so only enable this if you trust everything running in
+    /// the terminal.
+    AllowReadWrite,
+    /// Publishes every request as a [`Message::ClipboardRequest`], so the
+    /// application can ask the user.
+    Ask,
+}
+
+impl ClipboardPolicy {
+    fn allows_read(self) -> bool {
+        matches!(self, Self::AllowReadWrite | Self::Ask)
+    }
+}
+
+/// A program accessing the clipboard through OSC 52.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClipboardRequest {
+    /// Copies the text, or clears the clipboard if it is `None`.
+    Write { kind: Kind, text: Option<String> },
+    /// Reads the clipboard and sends its contents to the program.
+    Read { kind: Kind },
+}
+
+impl ClipboardRequest {
+    /// Performs the request, e.g. after the user allowed it.
+    pub(crate) fn perform(self) -> Task<Message> {
+        match self {
+            Self::Write { kind, text } => {
+                let text = text.unwrap_or_default();
+                match kind {
+                    Kind::Standard => iced::clipboard::write(text),
+                    Kind::Primary => iced::clipboard::write_primary(text),
+                }
+            }
+            Self::Read { kind } => {
+                let read = match kind {
+                    Kind::Standard => iced::clipboard::read(),
+                    Kind::Primary => iced::clipboard::read_primary(),
+                };
+                read.map(move |text| {
+                    Message::Action(KeyAction::SendBytes(read_response(
+                        kind,
+                        text.unwrap_or_default(),
+                    )))
+                })
+            }
+        }
+    }
+}
+
+/// Requests of the program that the widget hasn't handled yet.
+pub(crate) type PendingRequests = Arc<Mutex<Vec<ClipboardRequest>>>;
+
+/// Receives OSC 52 writes from wezterm_term.
+pub(crate) struct OscClipboard(pub PendingRequests);
+
+impl Clipboard for OscClipboard {
+    fn set_contents(
+        &self,
+        selection: ClipboardSelection,
+        data: Option<String>,
+    ) -> anyhow::Result<()> {
+        let kind = match selection {
+            ClipboardSelection::Clipboard => Kind::Standard,
+            ClipboardSelection::PrimarySelection => Kind::Primary,
+        };
+
+        push(&self.0, ClipboardRequest::Write { kind, text: data });
+        Ok(())
+    }
+}
+
+/// Queues the request, dropping older writes to the same clipboard, since
+/// only the latest contents matter.
+fn push(requests: &Mutex<Vec<ClipboardRequest>>, request: ClipboardRequest) {
+    let mut requests = requests.lock().unwrap();
+
+    if let ClipboardRequest::Write { kind, .. } = &request {
+        requests.retain(|pending| {
+            !matches!(pending, ClipboardRequest::Write { kind: pending, .. } if pending == kind)
+        });
+    }
+    requests.push(request);
+}
+
+/// Drops denied requests and finds OSC 52 queries in the output.
+///
+/// wezterm_term ignores queries, so the output is parsed a second time, but only
+/// if the policy allows reading at all.
+pub(crate) fn process_output(
+    parser: &mut Parser,
+    requests: &Mutex<Vec<ClipboardRequest>>,
+    policy: ClipboardPolicy,
+    bytes: &[u8],
+) {
+    if policy == ClipboardPolicy::Deny {
+        requests.lock().unwrap().clear();
+    }
+    if !policy.allows_read() {
+        return;
+    }
+
+    parser.parse(bytes, |action| {
+        if let Action::OperatingSystemCommand(osc) = action
+            && let OperatingSystemCommand::QuerySelection(selection) = *osc
+        {
+            let kind = if selection.contains(Selection::PRIMARY) {
+                Kind::Primary
+            } else {
+                Kind::Standard
+            };
+            push(requests, ClipboardRequest::Read { kind });
+        }
+    });
+}
+
+/// Handles the pending requests according to the policy.
+pub(crate) fn handle_requests(
+    requests: &Mutex<Vec<ClipboardRequest>>,
+    policy: ClipboardPolicy,
+    clipboard: &mut dyn iced::advanced::Clipboard,
+    shell: &mut Shell<'_, Message>,
+) {
+    let requests = std::mem::take(&mut *requests.lock().unwrap());
+
+    for request in requests {
+        match (policy, request) {
+            (ClipboardPolicy::Ask, request) => shell.publish(Message::ClipboardRequest(request)),
+            (
+                ClipboardPolicy::AllowWrite | ClipboardPolicy::AllowReadWrite,
+                ClipboardRequest::Write { kind, text },
+            ) => clipboard.write(kind, text.unwrap_or_default()),
+            (ClipboardPolicy::AllowReadWrite, ClipboardRequest::Read { kind }) => {
+                let text = clipboard.read(kind).unwrap_or_default();
+                shell.publish(Message::Action(KeyAction::SendBytes(read_response(
+                    kind, text,
+                ))));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The OSC 52 sequence answering a query.
+fn read_response(kind: Kind, text: String) -> Vec<u8> {
+    let selection = match kind {
+        Kind::Standard => Selection::CLIPBOARD,
+        Kind::Primary => Selection::PRIMARY,
+    };
+
+    OperatingSystemCommand::SetSelection(selection, text)
+        .to_string()
+        .into_bytes()
+}
@@ -0,0 +1,380 @@
+use std::time::{Duration, Instant};
+
+use iced::{
+    Alignment, Element, Length, Subscription, Task,
+    widget::{button, column, row, slider, text},
+};
+use serde_json::Value;
+
+use crate::{ClipboardPolicy, Terminal, TerminalSize, keybindings::KeyAction};
+
+const TICK_INTERVAL: Duration = Duration::from_millis(16);
+const SPEEDS: [f32; 5] = [0.5, 1.0, 2.0, 4.0, 8.0];
+
+#[derive(Debug, thiserror::Error)]
+pub enum CastError {
+    #[error("the recording is empty")]
+    Empty,
+    #[error("invalid asciicast header: {0}")]
+    InvalidHeader(String),
+    #[error("invalid event on line {0}")]
+    InvalidEvent(usize),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
+#[derive(Debug, Clone)]
+pub enum PlayerMessage {
+    Tick(Instant),
+    Play,
+    Pause,
+    TogglePlayback,
+    /// Sets the playback speed, `1.0` being real time, see [`Player::set_speed`].
+    SetSpeed(f32),
+    /// Jumps to the given position in the (idle compressed) recording.
+    Seek(Duration),
+    Terminal(crate::Message),
+}
+
+#[derive(Debug, Clone)]
+enum EventKind {
+    Output(String),
+    Resize { cols: usize, rows: usize },
+}
+
+#[derive(Debug, Clone)]
+struct Event {
+    /// The time since the start, as recorded.
+    recorded: Duration,
+    /// The time since the start, with long pauses shortened to the idle time limit.
+    time: Duration,
+    kind: EventKind,
+}
+
+/// Plays an asciicast v2 recording in a [`Terminal`].
+///
+/// Route [`PlayerMessage`]s to [`Player::update`] and use [`Player::subscription`]
+/// to drive the playback.
+pub struct Player {
+    terminal: Terminal,
+    size: (usize, usize),
+    events: Vec<Event>,
+    /// The index of the next event to play.
+    next_event: usize,
+    position: Duration,
+    playing: bool,
+    speed: f32,
+    idle_time_limit: Option<Duration>,
+    last_tick: Option<Instant>,
+}
+
+impl Player {
+    /// Parses a recording in the asciicast v2 format.
+    ///
+    /// Input events and markers are ignored. The idle time limit of the header is used, if set.
+    pub fn new(cast: &str) -> Result<Self, CastError> {
+        let mut lines = cast
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty());
+
+        let (_, header) = lines.next().ok_or(CastError::Empty)?;
+        let header: Value = serde_json::from_str(header)?;
+
+        if header["version"] != 2 {
+            return Err(CastError::InvalidHeader(
+                "only version 2 is supported".into(),
+            ));
+        }
+
+        let dimension = |name: &str| {
+            header[name]
+                .as_u64()
+                .map(|value| value as usize)
+                .ok_or_else(|| CastError::InvalidHeader(format!("missing {name}")))
+        };
+        let size = (dimension("width")?, dimension("height")?);
+
+        let mut events = Vec::new();
+
+        for (index, line) in lines {
+            let event: Value = serde_json::from_str(line)?;
+            let invalid = || CastError::InvalidEvent(index + 1);
+
+            let recorded = event[0]
+                .as_f64()
+                .and_then(|time| Duration::try_from_secs_f64(time).ok())
+                .ok_or_else(invalid)?;
+            let data = event[2].as_str().ok_or_else(invalid)?;
+
+            let kind = match event[1].as_str().ok_or_else(invalid)? {
+                "o" => EventKind::Output(data.to_string()),
+                "r" => {
+                    let (cols, rows) = data.split_once('x').ok_or_else(invalid)?;
+                    EventKind::Resize {
+                        cols: cols.parse().map_err(|_| invalid())?,
+                        rows: rows.parse().map_err(|_| invalid())?,
+                    }
+                }
+                _ => continue,
+            };
+
+            events.push(Event {
+                recorded,
+                time: recorded,
+                kind,
+            });
+        }
+
+        let mut player = Self {
+            terminal: Self::new_terminal(size),
+            size,
+            events,
+            next_event: 0,
+            position: Duration::ZERO,
+            playing: false,
+            speed: 1.0,
+            idle_time_limit: None,
+            last_tick: None,
+        };
+
+        player.set_idle_time_limit(
+            header["idle_time_limit"]
+                .as_f64()
+                .and_then(|limit| Duration::try_from_secs_f64(limit).ok()),
+        );
+
+        Ok(player)
+    }
+
+    fn new_terminal((cols, rows): (usize, usize)) -> Terminal {
+        // the recording drives the terminal, so there is nothing to write to,
+        // and replayed OSC 52 sequences shouldn't touch the clipboard.
+        // The size is given by the recording, so the widget must not ask for another one.
+        Terminal::new(rows as u16, cols as u16, Box::new(std::io::sink()))
+            .clipboard_policy(ClipboardPolicy::Deny)
+            .fixed_size()
+    }
+
+    pub fn update(&mut self, message: PlayerMessage) -> Task<PlayerMessage> {
+        match message {
+            PlayerMessage::Tick(now) => {
+                if self.playing {
+                    if let Some(last_tick) = self.last_tick {
+                        let elapsed = now.saturating_duration_since(last_tick);
+                        // very high speeds would overflow, which just means jumping to the end
+                        let advance =
+                            Duration::try_from_secs_f32(elapsed.as_secs_f32() * self.speed)
+                                .unwrap_or(Duration::MAX);
+                        self.advance_to(self.position.saturating_add(advance));
+                    }
+                    self.last_tick = Some(now);
+
+                    if self.next_event == self.events.len() {
+                        self.pause();
+                    }
+                }
+                Task::none()
+            }
+            PlayerMessage::Play => {
+                self.play();
+                Task::none()
+            }
+            PlayerMessage::Pause => {
+                self.pause();
+                Task::none()
+            }
+            PlayerMessage::TogglePlayback => {
+                if self.playing {
+                    self.pause();
+                } else {
+                    self.play();
+                }
+                Task::none()
+            }
+            PlayerMessage::SetSpeed(speed) => {
+                self.set_speed(speed);
+                Task::none()
+            }
+            PlayerMessage::Seek(position) => {
+                self.seek(position);
+                Task::none()
+            }
+            PlayerMessage::Terminal(message) => match message {
+                // the size is given by the recording, and there is no program to send input to
+                crate::Message::Resize(_)
+                | crate::Message::KeyPress { .. }
+                | crate::Message::Paste(_)
+                | crate::Message::Action(KeyAction::SendBytes(_)) => Task::none(),
+                message => self.terminal.update(message).map(PlayerMessage::Terminal),
+            },
+        }
+    }
+
+    /// Ticks while the recording is playing.
+    pub fn subscription(&self) -> Subscription<PlayerMessage> {
+        if self.playing {
+            iced::time::every(TICK_INTERVAL).map(PlayerMessage::Tick)
+        } else {
+            Subscription::none()
+        }
+    }
+
+    /// The terminal showing the recording.
+    pub fn view(&self) -> Element<'_, PlayerMessage> {
+        self.terminal.view().map(PlayerMessage::Terminal)
+    }
+
+    /// The terminal with a play/pause button, a timeline and a speed selector below it.
+    pub fn view_with_controls(&self) -> Element<'_, PlayerMessage> {
+        let duration = self.duration().as_secs_f32();
+        let next_speed = SPEEDS
+            .iter()
+            .find(|speed| **speed > self.speed)
+            .unwrap_or(&SPEEDS[0]);
+
+        let controls = row![
+            button(if self.playing { "Pause" } else { "Play" })
+                .on_press(PlayerMessage::TogglePlayback),
+            slider(0.0..=duration, self.position.as_secs_f32(), |position| {
+                PlayerMessage::Seek(Duration::from_secs_f32(position))
+            })
+            .step(0.1),
+            text(format!(
+                "{} / {}",
+                format_time(self.position),
+                format_time(self.duration())
+            )),
+            button(text(format!("{}x", self.speed))).on_press(PlayerMessage::SetSpeed(*next_speed)),
+        ]
+        .spacing(10)
+        .padding(5)
+        .align_y(Alignment::Center);
+
+        column![self.view(), controls]
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+
+    pub fn play(&mut self) {
+        // start over once the end is reached
+        if self.next_event == self.events.len() {
+            self.seek(Duration::ZERO);
+        }
+
+        self.playing = true;
+        self.last_tick = None;
+    }
+
+    pub fn pause(&mut self) {
+        self.playing = false;
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    /// Sets the playback speed, `1.0` being real time.
+    ///
+    /// Negative speeds are clamped to `0.0`, which stalls the playback.
+    /// NaN and infinite speeds are ignored.
+    pub fn set_speed(&mut self, speed: f32) {
+        if speed.is_finite() {
+            self.speed = speed.max(0.0);
+        }
+    }
+
+    /// Shortens pauses in the recording to at most `limit`.
+    pub fn set_idle_time_limit(&mut self, limit: Option<Duration>) {
+        self.idle_time_limit = limit;
+
+        let mut previous = Duration::ZERO;
+        let mut time = Duration::ZERO;
+
+        for event in &mut self.events {
+            let gap = event.recorded.saturating_sub(previous);
+            time += limit.map_or(gap, |limit| gap.min(limit));
+            previous = event.recorded;
+            event.time = time;
+        }
+
+        // keep showing the same event
+        let position = self
+            .next_event
+            .checked_sub(1)
+            .map_or(Duration::ZERO, |index| self.events[index].time);
+        self.position = position;
+    }
+
+    pub fn idle_time_limit(&self) -> Option<Duration> {
+        self.idle_time_limit
+    }
+
+    /// The current position in the (idle compressed) recording.
+    pub fn position(&self) -> Duration {
+        self.position
+    }
+
+    /// The length of the (idle compressed) recording.
+    pub fn duration(&self) -> Duration {
+        self.events
+            .last()
+            .map_or(Duration::ZERO, |event| event.time)
+    }
+
+    /// Jumps to the position.
+    ///
+    /// Jumping backwards replays the recording from the start, since the terminal
+    /// state can't be rewound.
+    pub fn seek(&mut self, position: Duration) {
+        if position < self.position {
+            self.terminal = Self::new_terminal(self.size);
+            self.next_event = 0;
+        }
+
+        self.advance_to(position);
+        self.position = position.min(self.duration());
+    }
+
+    pub fn terminal(&self) -> &Terminal {
+        &self.terminal
+    }
+
+    /// Plays all events up to the position.
+    fn advance_to(&mut self, position: Duration) {
+        // collect consecutive output, so seeking through long recordings stays fast
+        let mut output = String::new();
+
+        while let Some(event) = self
+            .events
+            .get(self.next_event)
+            .filter(|event| event.time <= position)
+        {
+            match &event.kind {
+                EventKind::Output(data) => output.push_str(data),
+                EventKind::Resize { cols, rows } => {
+                    self.terminal.advance_bytes(std::mem::take(&mut output));
+                    self.terminal.resize(TerminalSize {
+                        rows: *rows,
+                        cols: *cols,
+                        ..Default::default()
+                    });
+                }
+            }
+            self.next_event += 1;
+        }
+
+        self.terminal.advance_bytes(output);
+        self.position = position.min(self.duration());
+    }
+}
+
+fn format_time(time: Duration) -> String {
+    let seconds = time.as_secs();
+    format!("{:02}:{:02}", seconds / 60, seconds % 60)
+}
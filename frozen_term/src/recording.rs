@@ -0,0 +1,122 @@
+use std::{
+    io::Write,
+    sync::Mutex,
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+use serde_json::json;
+use wezterm_term::TerminalSize;
+
+/// Options for [`Terminal::start_recording`](crate::Terminal::start_recording).
+#[derive(Debug, Clone, Default)]
+pub struct RecordingOptions {
+    /// Also records everything written to the program, including typed passwords.
+    pub record_input: bool,
+    pub title: Option<String>,
+}
+
+/// Writes a session in the asciicast v2 format, one JSON event per line.
+pub(crate) struct Recorder {
+    writer: Box<dyn Write + Send>,
+    start: Instant,
+    record_input: bool,
+    /// Incomplete UTF-8 sequences at the end of the last chunk, which are
+    /// completed by the next one.
+    pending_output: Vec<u8>,
+    pending_input: Vec<u8>,
+}
+
+impl Recorder {
+    /// Writes the header and starts the clock.
+    pub fn start(
+        mut writer: Box<dyn Write + Send>,
+        size: &TerminalSize,
+        options: RecordingOptions,
+    ) -> std::io::Result<Self> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default();
+
+        let mut header = json!({
+            "version": 2,
+            "width": size.cols,
+            "height": size.rows,
+            "timestamp": timestamp,
+        });
+        if let Some(title) = options.title {
+            header["title"] = title.into();
+        }
+        writeln!(writer, "{header}")?;
+
+        Ok(Self {
+            writer,
+            start: Instant::now(),
+            record_input: options.record_input,
+            pending_output: Vec::new(),
+            pending_input: Vec::new(),
+        })
+    }
+
+    pub fn output(&mut self, bytes: &[u8]) -> std::io::Result<()> {
+        let text = take_utf8(&mut self.pending_output, bytes);
+        self.event("o", &text)
+    }
+
+    pub fn input(&mut self, bytes: &[u8]) -> std::io::Result<()> {
+        if !self.record_input {
+            return Ok(());
+        }
+
+        let text = take_utf8(&mut self.pending_input, bytes);
+        self.event("i", &text)
+    }
+
+    pub fn resize(&mut self, size: &TerminalSize) -> std::io::Result<()> {
+        self.event("r", &format!("{}x{}", size.cols, size.rows))
+    }
+
+    pub fn finish(mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+
+    fn event(&mut self, kind: &str, data: &str) -> std::io::Result<()> {
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        let time = self.start.elapsed().as_secs_f64();
+        writeln!(self.writer, "{}", json!([time, kind, data]))
+    }
+}
+
+/// Decodes the bytes, keeping an incomplete UTF-8 sequence at the end for later.
+fn take_utf8(pending: &mut Vec<u8>, bytes: &[u8]) -> String {
+    pending.extend_from_slice(bytes);
+
+    let complete = match std::str::from_utf8(pending) {
+        Ok(_) => pending.len(),
+        Err(error) if error.error_len().is_none() => error.valid_up_to(),
+        // invalid bytes are replaced, there is nothing to wait for
+        Err(_) => pending.len(),
+    };
+
+    let rest = pending.split_off(complete);
+    let text = String::from_utf8_lossy(pending).into_owned();
+    *pending = rest;
+    text
+}
+
+/// Runs the event on the active recorder, stopping the recording if writing fails.
+pub(crate) fn record(
+    recorder: &Mutex<Option<Recorder>>,
+    event: impl FnOnce(&mut Recorder) -> std::io::Result<()>,
+) {
+    let mut recorder = recorder.lock().unwrap();
+
+    if let Some(active) = recorder.as_mut()
+        && event(active).is_err()
+    {
+        *recorder = None;
+    }
+}
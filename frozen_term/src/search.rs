@@ -0,0 +1,222 @@
+use std::ops::Range;
+
+use regex::{Regex, RegexBuilder};
+use wezterm_term::{Line, Screen, StableRowIndex, Terminal, TerminalSize};
+
+use crate::selection::GridPoint;
+
+/// What to search for in the terminal output.
+#[derive(Debug, Clone)]
+pub enum SearchPattern {
+    /// Matches the text exactly.
+    Text(String),
+    /// Matches the text, ignoring case.
+    CaseInsensitive(String),
+    Regex(Regex),
+}
+
+impl SearchPattern {
+    /// Fails if the text is too long to be compiled, e.g. a huge pasted query.
+    pub(crate) fn regex(&self) -> Result<Regex, regex::Error> {
+        match self {
+            Self::Text(text) => Regex::new(&regex::escape(text)),
+            Self::CaseInsensitive(text) => RegexBuilder::new(&regex::escape(text))
+                .case_insensitive(true)
+                .build(),
+            Self::Regex(regex) => Ok(regex.clone()),
+        }
+    }
+}
+
+/// A match of a search, from `start` up to the exclusive `end`.
+///
+/// Matches can span several rows if the line was wrapped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchMatch {
+    pub start: GridPoint,
+    pub end: GridPoint,
+}
+
+impl SearchMatch {
+    pub(crate) fn contains(&self, point: GridPoint) -> bool {
+        self.start <= point && point < self.end
+    }
+}
+
+/// The search highlighted in the terminal, and the match navigated to.
+#[derive(Debug, Clone)]
+pub(crate) struct ActiveSearch {
+    /// Compiled once, as the matches are updated after every output.
+    regex: Regex,
+    pub matches: Vec<SearchMatch>,
+    pub current: Option<usize>,
+    /// The top row of the screen when the matches were updated.
+    ///
+    /// Output only changes the lines on the screen, so the matches above it stay valid
+    /// until the size changes (which reflows the scrollback) or the alternate screen is toggled.
+    screen_top: StableRowIndex,
+    size: TerminalSize,
+    alt_screen: bool,
+}
+
+impl ActiveSearch {
+    pub fn new(term: &Terminal, pattern: &SearchPattern) -> Result<Self, regex::Error> {
+        let regex = pattern.regex()?;
+        let screen = term.screen();
+        let matches = find(screen, &regex, 0..screen.scrollback_rows());
+
+        Ok(Self {
+            regex,
+            matches,
+            current: None,
+            screen_top: screen_top(screen),
+            size: term.get_size(),
+            alt_screen: term.is_alt_screen_active(),
+        })
+    }
+
+    /// Searches the lines which could have changed since the last update again,
+    /// keeping the current match if it still exists.
+    pub fn refresh(&mut self, term: &Terminal) {
+        let screen = term.screen();
+        let size = term.get_size();
+        let alt_screen = term.is_alt_screen_active();
+
+        let start = match screen.stable_row_to_phys(self.screen_top) {
+            Some(phys) if size == self.size && alt_screen == self.alt_screen => {
+                logical_line_range(screen, phys).start
+            }
+            _ => 0,
+        };
+        let oldest_row = screen.phys_to_stable_row_index(0);
+        let first_changed_row = screen.phys_to_stable_row_index(start);
+
+        let current = self.current.map(|current| self.matches[current]);
+        self.matches
+            .retain(|found| found.start.row >= oldest_row && found.start.row < first_changed_row);
+        self.matches
+            .extend(find(screen, &self.regex, start..screen.scrollback_rows()));
+        self.current =
+            current.and_then(|current| self.matches.iter().position(|found| *found == current));
+
+        self.screen_top = screen_top(screen);
+        self.size = size;
+        self.alt_screen = alt_screen;
+    }
+}
+
+fn screen_top(screen: &Screen) -> StableRowIndex {
+    screen.phys_to_stable_row_index(screen.scrollback_rows() - screen.physical_rows)
+}
+
+/// Searches the physical rows, which have to start at the beginning of a logical line.
+pub(crate) fn find(screen: &Screen, regex: &Regex, phys_range: Range<usize>) -> Vec<SearchMatch> {
+    let first_phys = phys_range.start;
+    let lines = screen.lines_in_phys_range(phys_range);
+    let mut matches = Vec::new();
+    let mut start = 0;
+
+    while start < lines.len() {
+        let mut end = start + 1;
+        while end < lines.len() && lines[end - 1].last_cell_was_wrapped() {
+            end += 1;
+        }
+
+        let line = LogicalLine::new(
+            &lines[start..end],
+            screen.phys_to_stable_row_index(first_phys + start),
+        );
+
+        for found in regex.find_iter(&line.text) {
+            // an empty match doesn't cover any cell, so there is nothing to highlight
+            let mut cells = line.cells(found.range());
+            let Some((row, first)) = cells.next() else {
+                continue;
+            };
+            let (last_row, last) = cells.last().unwrap_or((row, first.clone()));
+
+            matches.push(SearchMatch {
+                start: GridPoint {
+                    row,
+                    col: first.start,
+                },
+                end: GridPoint {
+                    row: last_row,
+                    col: last.end,
+                },
+            });
+        }
+
+        start = end;
+    }
+
+    matches
+}
+
+/// The text of a line, following wrapped lines, together with the cells of every grapheme in it.
+pub(crate) struct LogicalLine {
+    pub text: String,
+    graphemes: Vec<(usize, StableRowIndex, Range<usize>)>,
+}
+
+impl LogicalLine {
+    /// Joins the lines, the first of them being at `first_row`.
+    pub fn new(lines: &[Line], first_row: StableRowIndex) -> Self {
+        let mut text = String::new();
+        let mut graphemes = Vec::new();
+
+        for (line, row) in lines.iter().zip(first_row..) {
+            for cell in line.visible_cells() {
+                graphemes.push((
+                    text.len(),
+                    row,
+                    cell.cell_index()..cell.cell_index() + cell.width(),
+                ));
+                text.push_str(cell.str());
+            }
+        }
+
+        Self { text, graphemes }
+    }
+
+    /// The byte offset of the grapheme at the given cell.
+    pub fn offset(&self, point: GridPoint) -> Option<usize> {
+        self.graphemes
+            .iter()
+            .find(|(_, row, cols)| *row == point.row && cols.contains(&point.col))
+            .map(|(offset, _, _)| *offset)
+    }
+
+    /// The cells of all graphemes starting inside the byte range of the text.
+    pub fn cells(
+        &self,
+        range: Range<usize>,
+    ) -> impl Iterator<Item = (StableRowIndex, Range<usize>)> + '_ {
+        self.graphemes
+            .iter()
+            .filter(move |(offset, _, _)| range.contains(offset))
+            .map(|(_, row, cols)| (*row, cols.clone()))
+    }
+}
+
+/// The physical rows of the logical line containing the given row.
+pub(crate) fn logical_line_range(screen: &Screen, phys: usize) -> Range<usize> {
+    let is_wrapped = |phys: usize| {
+        screen
+            .lines_in_phys_range(phys..phys + 1)
+            .first()
+            .is_some_and(|line| line.last_cell_was_wrapped())
+    };
+
+    let mut start = phys;
+    while start > 0 && is_wrapped(start - 1) {
+        start -= 1;
+    }
+
+    let mut end = phys + 1;
+    while end < screen.scrollback_rows() && is_wrapped(end - 1) {
+        end += 1;
+    }
+
+    start..end
+}
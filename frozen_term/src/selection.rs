@@ -0,0 +1,241 @@
+use wezterm_term::StableRowIndex;
+
+use crate::search::{self, LogicalLine};
+
+/// A cell on the terminal grid, addressed by column and stable row index.
+///
+/// Stable rows keep pointing at the same line while new output scrolls the screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct GridPoint {
+    pub row: StableRowIndex,
+    pub col: usize,
+}
+
+/// What a selection extends by while dragging.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SelectionMode {
+    Cell,
+    /// Started with a double-click.
+    Word,
+    /// Started with a triple-click, following wrapped lines.
+    Line,
+    /// A rectangle, started with Alt+drag.
+    Block,
+}
+
+/// The first cell and the (exclusive) end of a cell, word or line.
+pub(crate) type Unit = (GridPoint, GridPoint);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Selection {
+    mode: SelectionMode,
+    /// The unit the selection started at, which always stays selected.
+    anchor: Unit,
+    head: Unit,
+}
+
+impl Selection {
+    pub fn new(mode: SelectionMode, anchor: Unit) -> Self {
+        Self {
+            mode,
+            anchor,
+            head: anchor,
+        }
+    }
+
+    pub fn mode(&self) -> SelectionMode {
+        self.mode
+    }
+
+    pub fn extend(&mut self, head: Unit) {
+        self.head = head;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        let (start, end) = self.ordered();
+
+        match self.mode {
+            SelectionMode::Block => start.col == end.col,
+            _ => start == end,
+        }
+    }
+
+    /// Returns the start and the (exclusive) end of the selection in reading order.
+    ///
+    /// For block selections, these are the top left and bottom right corners.
+    pub fn ordered(&self) -> (GridPoint, GridPoint) {
+        if self.mode == SelectionMode::Block {
+            let (anchor, head) = (self.anchor, self.head);
+
+            return (
+                GridPoint {
+                    row: anchor.0.row.min(head.0.row),
+                    col: anchor.0.col.min(head.0.col),
+                },
+                GridPoint {
+                    row: anchor.1.row.max(head.1.row),
+                    col: anchor.1.col.max(head.1.col),
+                },
+            );
+        }
+
+        if self.head.0 < self.anchor.0 {
+            (self.head.0, self.anchor.1)
+        } else {
+            (self.anchor.0, self.head.1)
+        }
+    }
+
+    /// Returns the selected column range of the row, if the row is part of the selection.
+    pub fn cols_in_row(&self, row: StableRowIndex, cols: usize) -> Option<std::ops::Range<usize>> {
+        let (start, end) = self.ordered();
+
+        if row < start.row || row > end.row {
+            return None;
+        }
+
+        let (first, last) = match self.mode {
+            SelectionMode::Block => (start.col, end.col),
+            _ => (
+                if row == start.row { start.col } else { 0 },
+                if row == end.row { end.col } else { cols },
+            ),
+        };
+
+        (first < last).then_some(first..last.min(cols))
+    }
+
+    /// Extracts the selected text.
+    ///
+    /// Wrapped lines are joined, all others are separated by a newline.
+    /// The rows of block selections are always separated.
+    pub fn text(&self, term: &wezterm_term::Terminal) -> String {
+        let screen = term.screen();
+        let (start, end) = self.ordered();
+        let phys_range = screen.stable_range(&(start.row..end.row + 1));
+        let cols = screen.physical_cols;
+
+        let mut text = String::new();
+
+        for (line, row) in screen
+            .lines_in_phys_range(phys_range.clone())
+            .iter()
+            .zip(phys_range.map(|phys| screen.phys_to_stable_row_index(phys)))
+        {
+            let Some(range) = self.cols_in_row(row, cols.max(line.len())) else {
+                continue;
+            };
+
+            let part = line.columns_as_str(range);
+
+            if line.last_cell_was_wrapped() && row != end.row && self.mode != SelectionMode::Block {
+                text.push_str(&part);
+            } else {
+                text.push_str(part.trim_end());
+                if row != end.row {
+                    text.push('\n');
+                }
+            }
+        }
+
+        text
+    }
+}
+
+/// The word containing the cell, following wrapped lines.
+///
+/// Words are made of alphanumeric characters and `word_chars`. Runs of whitespace
+/// or of the same other character are selected as a whole, too.
+pub(crate) fn word_at(term: &wezterm_term::Terminal, point: GridPoint, word_chars: &str) -> Unit {
+    let class = |c: char| {
+        if c.is_alphanumeric() || word_chars.contains(c) {
+            CharClass::Word
+        } else if c.is_whitespace() {
+            CharClass::Whitespace
+        } else {
+            CharClass::Other(c)
+        }
+    };
+
+    let Some((line, offset)) = logical_line_at(term, point) else {
+        return (point, point);
+    };
+    let Some(clicked) = line.text[offset..].chars().next().map(class) else {
+        return (point, point);
+    };
+
+    let start = line.text[..offset]
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| class(*c) == clicked)
+        .last()
+        .map_or(offset, |(index, _)| index);
+    let end = line.text[offset..]
+        .char_indices()
+        .find(|(_, c)| class(*c) != clicked)
+        .map_or(line.text.len(), |(index, _)| offset + index);
+
+    cells_of(&line, start..end).unwrap_or((point, point))
+}
+
+/// The whole line containing the row, following wrapped lines.
+pub(crate) fn line_at(term: &wezterm_term::Terminal, point: GridPoint) -> Unit {
+    let screen = term.screen();
+    let Some(phys) = screen.stable_row_to_phys(point.row) else {
+        return (point, point);
+    };
+    let range = search::logical_line_range(screen, phys);
+
+    (
+        GridPoint {
+            row: screen.phys_to_stable_row_index(range.start),
+            col: 0,
+        },
+        GridPoint {
+            row: screen.phys_to_stable_row_index(range.end - 1),
+            col: screen.physical_cols,
+        },
+    )
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CharClass {
+    Word,
+    Whitespace,
+    Other(char),
+}
+
+/// The logical line containing the cell, and the byte offset of the cell in it.
+pub(crate) fn logical_line_at(
+    term: &wezterm_term::Terminal,
+    point: GridPoint,
+) -> Option<(LogicalLine, usize)> {
+    let screen = term.screen();
+    let phys = screen.stable_row_to_phys(point.row)?;
+    let range = search::logical_line_range(screen, phys);
+    let line = LogicalLine::new(
+        &screen.lines_in_phys_range(range.clone()),
+        screen.phys_to_stable_row_index(range.start),
+    );
+    let offset = line.offset(point)?;
+
+    Some((line, offset))
+}
+
+/// The first cell and the end of the last cell of the text in the byte range.
+pub(crate) fn cells_of(line: &LogicalLine, range: std::ops::Range<usize>) -> Option<Unit> {
+    let mut cells = line.cells(range);
+    let (first_row, first_cols) = cells.next()?;
+    let (last_row, last_cols) = cells.last().unwrap_or((first_row, first_cols.clone()));
+
+    Some((
+        GridPoint {
+            row: first_row,
+            col: first_cols.start,
+        },
+        GridPoint {
+            row: last_row,
+            col: last_cols.end,
+        },
+    ))
+}
@@ -0,0 +1,70 @@
+use regex::Regex;
+
+use crate::{
+    links::{PATH_REGEX, URL_REGEX},
+    selection::{self, GridPoint, Unit},
+};
+
+const IPV4_REGEX: &str = r"\b(?:\d{1,3}\.){3}\d{1,3}(?::\d{1,5})?\b";
+const IPV6_REGEX: &str = r"\b(?:(?:[0-9a-fA-F]{1,4}:){7}[0-9a-fA-F]{1,4}|(?:[0-9a-fA-F]{1,4}:)+:(?:[0-9a-fA-F]{1,4}:)*[0-9a-fA-F]{1,4})\b";
+const GIT_HASH_REGEX: &str = r"\b[0-9a-f]{7,40}\b";
+const DOUBLE_QUOTED_REGEX: &str = r#""(?P<selection>[^"]+)""#;
+/// Only quotes at the start of a word, so apostrophes in text aren't taken for quotes.
+const SINGLE_QUOTED_REGEX: &str = r"(?:^|[\s=(\[{])'(?P<selection>[^'\s][^']*)'";
+
+/// A rule for selecting more than a word when double-clicking, e.g. a whole URL.
+///
+/// Rules are checked in order, the first rule matching at the mouse position wins.
+/// If no rule matches, the word at the mouse position is selected.
+#[derive(Debug, Clone)]
+pub struct SmartSelectionRule {
+    regex: Regex,
+}
+
+impl SmartSelectionRule {
+    /// Creates a rule selecting the whole match, or only the `selection`
+    /// capture group if present.
+    pub fn new(regex: &str) -> Result<Self, regex::Error> {
+        Ok(Self {
+            regex: Regex::new(regex)?,
+        })
+    }
+
+    /// The default rules, selecting URLs, paths, IP addresses, git hashes and quoted strings.
+    pub fn defaults() -> Vec<Self> {
+        vec![
+            Self::new(URL_REGEX).unwrap(),
+            Self::new(PATH_REGEX).unwrap(),
+            Self::new(IPV4_REGEX).unwrap(),
+            Self::new(IPV6_REGEX).unwrap(),
+            Self::new(GIT_HASH_REGEX).unwrap(),
+            Self::new(DOUBLE_QUOTED_REGEX).unwrap(),
+            Self::new(SINGLE_QUOTED_REGEX).unwrap(),
+        ]
+    }
+}
+
+/// The cells matched by the first rule at the given cell, following wrapped lines.
+pub(crate) fn rule_match_at(
+    term: &wezterm_term::Terminal,
+    rules: &[SmartSelectionRule],
+    point: GridPoint,
+) -> Option<Unit> {
+    if rules.is_empty() {
+        return None;
+    }
+
+    let (line, clicked) = selection::logical_line_at(term, point)?;
+
+    rules.iter().find_map(|rule| {
+        let selected = rule.regex.captures_iter(&line.text).find_map(|captures| {
+            let selected = captures
+                .name("selection")
+                .unwrap_or(captures.get(0).unwrap());
+
+            selected.range().contains(&clicked).then_some(selected)
+        })?;
+
+        selection::cells_of(&line, selected.range())
+    })
+}
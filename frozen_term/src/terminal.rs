@@ -1,29 +1,51 @@
 use std::{
-    sync::Arc,
+    collections::VecDeque,
+    io::Write,
+    ops::Range,
+    sync::{
+        Arc, Condvar, Mutex, MutexGuard,
+        atomic::{AtomicUsize, Ordering},
+        mpsc,
+    },
     time::{Duration, Instant},
 };
 
 use iced::{
-    Border, Color, Element, Length, Point, Rectangle, Size, Task, Vector,
+    Border, Color, Element, Length, Pixels, Point, Rectangle, Size, Task, Vector,
     advanced::{
-        Shell, Text,
+        Shell, Text, clipboard,
         layout::Node,
+        mouse::{Click, click},
         renderer::Quad,
         text::{Paragraph, Renderer},
-        widget::operation::Focusable,
+        widget::{Operation, operation, operation::Focusable},
     },
     alignment::{Horizontal, Vertical},
-    keyboard,
+    keyboard, mouse,
     widget::text::{LineHeight, Shaping, Wrapping},
     window::RedrawRequest,
 };
-use termwiz::surface::{CursorShape, CursorVisibility};
+use termwiz::surface::{CursorShape, CursorVisibility, Line};
 use wezterm_term::{
-    CellAttributes, CursorPosition, TerminalConfiguration,
-    color::{ColorAttribute, ColorPalette},
+    TerminalConfiguration,
+    color::{ColorAttribute, ColorPalette, SrgbaTuple},
+};
+
+use crate::{
+    copy_mode::{CopyMode, CopyModeAction},
+    export::{self, ExportFormat},
+    images::{self, ImageCache, ImagePlacement},
+    input::{self, KeyEncoding, KeyNotationError},
+    keybindings::{KeyAction, KeyBindings},
+    links::{self, HoveredLink, Link, LinkRule},
+    osc52::{self, ClipboardPolicy, ClipboardRequest, OscClipboard, PendingRequests},
+    recording::{self, Recorder, RecordingOptions},
+    search::{self, ActiveSearch, SearchMatch, SearchPattern},
+    selection::{self, GridPoint, Selection, SelectionMode, Unit},
+    smart_selection::{self, SmartSelectionRule},
 };
 
-pub use wezterm_term::TerminalSize;
+pub use wezterm_term::{Cell, CellAttributes, CursorPosition, StableRowIndex, TerminalSize};
 
 #[derive(Debug, Clone)]
 pub enum Message {
@@ -32,19 +54,219 @@ pub enum Message {
         modified_key: keyboard::key::Key,
         modifiers: keyboard::Modifiers,
     },
+    Paste(String),
+    /// Performs the action, as if its key binding was pressed.
+    ///
+    /// This is also how applications trigger actions from their own UI, e.g.
+    /// `Message::Action(KeyAction::ClearScreen)`, [`KeyAction::ClearScrollback`],
+    /// [`KeyAction::SoftReset`] or [`KeyAction::HardReset`] for a menu entry.
+    Action(KeyAction),
+    /// The widget gained or lost keyboard focus.
+    FocusChanged(bool),
+    /// A link was Ctrl+clicked.
+    ///
+    /// frozen_term does not open links by itself, handle this in your application.
+    OpenLink(Link),
+    /// Scrolls just enough to show the row.
+    ScrollToRow(StableRowIndex),
+    /// The program accessed the clipboard with [`ClipboardPolicy::Ask`].
+    ///
+    /// Handle this in your application: pass it back as [`Message::AllowClipboardRequest`]
+    /// to allow it, or drop it to deny it.
+    ClipboardRequest(ClipboardRequest),
+    AllowClipboardRequest(ClipboardRequest),
 }
 
 pub struct Terminal {
     term: wezterm_term::Terminal,
     id: Option<Id>,
+    writer: SharedWriter,
+    key_bindings: KeyBindings,
+    key_encoding: KeyEncoding,
+    scroll_offset: usize,
+    zoom: f32,
+    cursor_blink_interval: Option<Duration>,
+    cursor_blink_timeout: Duration,
+    link_rules: Vec<LinkRule>,
+    smart_selection_rules: Vec<SmartSelectionRule>,
+    search: Option<ActiveSearch>,
+    /// Changes whenever the search highlights change, so the widget knows to update them.
+    search_version: usize,
+    recorder: Arc<Mutex<Option<Recorder>>>,
+    /// Hides the cursor and lets unbound key presses through, see [`LogViewer`](crate::LogViewer).
+    read_only: bool,
+    /// Keeps the size instead of following the widget size, see [`Player`](crate::Player).
+    fixed_size: bool,
+    /// Shared with wezterm_term, which reads the scrollback limit from it.
+    config: Arc<Config>,
+    scrollback_size: usize,
+    memory_budget: Option<usize>,
+    clipboard_policy: ClipboardPolicy,
+    /// Copies selections to the primary selection and pastes it on middle-click.
+    primary_selection: bool,
+    /// Characters besides alphanumerics that double-click selects as part of a word.
+    word_chars: String,
+    /// OSC 52 requests, handled by the widget since it has access to the clipboard.
+    clipboard_requests: PendingRequests,
+    /// Finds OSC 52 queries, which wezterm_term ignores.
+    osc_parser: termwiz::escape::parser::Parser,
+}
+
+/// Pasted through wezterm_term to find where bytes written by frozen_term belong,
+/// see [`SharedWriter`]. A noncharacter, which real pastes don't consist of.
+const WRITE_MARKER: &str = "\u{10FFFF}";
+
+/// The pty writer, shared between wezterm_term and frozen_term.
+///
+/// wezterm_term writes key presses, pastes and replies to queries from a background
+/// thread, while frozen_term writes some bytes itself, e.g. for key bindings or a
+/// [`KeyEncoding`]. To keep both in order, frozen_term's bytes are held back and
+/// [`WRITE_MARKER`] is pasted through wezterm_term. When the marker comes out, the
+/// held back bytes are written in its place.
+///
+/// The pty is written from another thread, so a program that doesn't read its input
+/// never blocks the UI. Everything written passes the recorder as input.
+#[derive(Clone)]
+struct SharedWriter(Arc<(Mutex<WriterState>, Condvar)>);
+
+struct WriterState {
+    /// Bytes written by frozen_term, waiting for their marker.
+    held_back: VecDeque<Vec<u8>>,
+    /// Writes sent to the pty thread which it hasn't finished yet.
+    queued: usize,
+}
+
+impl SharedWriter {
+    /// Starts the thread writing to the pty, returning the writer for wezterm_term.
+    fn new(
+        mut writer: Box<dyn Write + Send>,
+        recorder: Arc<Mutex<Option<Recorder>>>,
+    ) -> (Self, TermWriter) {
+        let state = WriterState {
+            held_back: VecDeque::new(),
+            queued: 0,
+        };
+        let shared = Self(Arc::new((Mutex::new(state), Condvar::new())));
+        let (sender, receiver) = mpsc::channel::<Vec<u8>>();
+
+        let thread_shared = shared.clone();
+        std::thread::spawn(move || {
+            // ends when wezterm_term drops the sender along with the terminal
+            for bytes in receiver {
+                // fails once the program exited, which its output already shows
+                let written = writer.write_all(&bytes).and_then(|()| writer.flush());
+                if written.is_ok() && !bytes.is_empty() {
+                    recording::record(&recorder, |recorder| recorder.input(&bytes));
+                }
+
+                let (state, written) = &*thread_shared.0;
+                state.lock().unwrap().queued -= 1;
+                written.notify_all();
+            }
+        });
+
+        let term_writer = TermWriter {
+            shared: shared.clone(),
+            sender,
+        };
+        (shared, term_writer)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, WriterState> {
+        self.0.0.lock().unwrap()
+    }
+
+    /// Holds the bytes back until wezterm_term passes on the next marker.
+    fn hold_back(&self, bytes: &[u8]) {
+        self.lock().held_back.push_back(bytes.to_vec());
+    }
+
+    /// Waits until all held back bytes reached the pty thread and it wrote
+    /// everything, or the timeout passed.
+    #[cfg(feature = "testing")]
+    fn wait_until_written(&self, timeout: Duration) {
+        let (state, written) = &*self.0;
+        let _state = written
+            .wait_timeout_while(state.lock().unwrap(), timeout, |state| {
+                !state.held_back.is_empty() || state.queued > 0
+            })
+            .unwrap();
+    }
+}
+
+/// The writer handed to wezterm_term, which calls it from its background thread.
+struct TermWriter {
+    shared: SharedWriter,
+    sender: mpsc::Sender<Vec<u8>>,
+}
+
+impl TermWriter {
+    fn is_marker(buf: &[u8]) -> bool {
+        // the marker is a paste, so it is bracketed if the program enabled bracketed paste
+        let unbracketed = buf
+            .strip_prefix(b"\x1b[200~")
+            .and_then(|buf| buf.strip_suffix(b"\x1b[201~"));
+
+        unbracketed.unwrap_or(buf) == WRITE_MARKER.as_bytes()
+    }
+}
+
+impl Write for TermWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let mut state = self.shared.lock();
+
+        let bytes = if Self::is_marker(buf) {
+            state.held_back.pop_front().unwrap_or_default()
+        } else {
+            buf.to_vec()
+        };
+
+        state.queued += 1;
+        self.sender
+            .send(bytes)
+            .map_err(|_| std::io::Error::from(std::io::ErrorKind::BrokenPipe))?;
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        // the pty thread flushes after every write
+        Ok(())
+    }
 }
 
 #[derive(Debug)]
-pub struct Config {}
+pub struct Config {
+    /// wezterm_term reads this whenever a line is scrolled into the scrollback,
+    /// so it can be changed while the terminal is running.
+    scrollback_size: AtomicUsize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            scrollback_size: AtomicUsize::new(DEFAULT_SCROLLBACK_SIZE),
+        }
+    }
+}
 
 impl TerminalConfiguration for Config {
+    fn scrollback_size(&self) -> usize {
+        self.scrollback_size.load(Ordering::Relaxed)
+    }
+
     fn color_palette(&self) -> wezterm_term::color::ColorPalette {
-        ColorPalette::default()
+        // applications can still change the cursor color through OSC 12
+        ColorPalette {
+            cursor_bg: SrgbaTuple(1.0, 1.0, 1.0, 1.0),
+            cursor_border: SrgbaTuple(1.0, 1.0, 1.0, 1.0),
+            cursor_fg: SrgbaTuple(0.0, 0.0, 0.0, 1.0),
+            ..ColorPalette::default()
+        }
+    }
+
+    fn enable_kitty_graphics(&self) -> bool {
+        true
     }
 }
 
@@ -56,12 +278,59 @@ impl Terminal {
             ..Default::default()
         };
 
-        let config = Config {};
+        let recorder = Arc::new(Mutex::new(None));
+        let (writer, term_writer) = SharedWriter::new(writer, recorder.clone());
+
+        let config = Arc::new(Config::default());
+        let mut term = wezterm_term::Terminal::new(
+            size,
+            config.clone(),
+            "frozen_term",
+            "0.1",
+            Box::new(term_writer),
+        );
 
-        let term =
-            wezterm_term::Terminal::new(size, Arc::new(config), "frozen_term", "0.1", writer);
+        let clipboard_requests = PendingRequests::default();
+        let clipboard: Arc<dyn wezterm_term::Clipboard> =
+            Arc::new(OscClipboard(clipboard_requests.clone()));
+        term.set_clipboard(&clipboard);
 
-        Self { term, id: None }
+        Self {
+            term,
+            id: None,
+            writer,
+            key_bindings: KeyBindings::default(),
+            key_encoding: KeyEncoding::default(),
+            scroll_offset: 0,
+            zoom: 1.0,
+            cursor_blink_interval: Some(DEFAULT_CURSOR_BLINK_INTERVAL),
+            cursor_blink_timeout: DEFAULT_CURSOR_BLINK_TIMEOUT,
+            link_rules: LinkRule::defaults(),
+            smart_selection_rules: SmartSelectionRule::defaults(),
+            search: None,
+            search_version: 0,
+            recorder,
+            read_only: false,
+            fixed_size: false,
+            config,
+            scrollback_size: DEFAULT_SCROLLBACK_SIZE,
+            memory_budget: None,
+            clipboard_policy: ClipboardPolicy::default(),
+            primary_selection: true,
+            word_chars: DEFAULT_WORD_CHARS.to_string(),
+            clipboard_requests,
+            osc_parser: termwiz::escape::parser::Parser::new(),
+        }
+    }
+
+    pub(crate) fn read_only(mut self) -> Self {
+        self.read_only = true;
+        self
+    }
+
+    pub(crate) fn fixed_size(mut self) -> Self {
+        self.fixed_size = true;
+        self
     }
 
     pub fn id(mut self, id: impl Into<Id>) -> Self {
@@ -69,39 +338,702 @@ impl Terminal {
         self
     }
 
+    /// Replaces the default [`KeyBindings`].
+    pub fn key_bindings(mut self, key_bindings: KeyBindings) -> Self {
+        self.key_bindings = key_bindings;
+        self
+    }
+
+    /// Sets how Alt, Backspace and Delete are encoded.
+    pub fn key_encoding(mut self, key_encoding: KeyEncoding) -> Self {
+        self.key_encoding = key_encoding;
+        self
+    }
+
+    /// Sets how fast a blinking cursor blinks. `None` disables blinking altogether.
+    pub fn cursor_blink_interval(mut self, interval: Option<Duration>) -> Self {
+        self.cursor_blink_interval = interval;
+        self
+    }
+
+    /// Sets how long the cursor keeps blinking after the last key press.
+    ///
+    /// Once the cursor stops blinking, the terminal does not request any redraws
+    /// until something changes.
+    pub fn cursor_blink_timeout(mut self, timeout: Duration) -> Self {
+        self.cursor_blink_timeout = timeout;
+        self
+    }
+
+    /// Replaces the rules used to detect links in the terminal output.
+    ///
+    /// Pass an empty list to only support OSC 8 hyperlinks.
+    pub fn link_rules(mut self, rules: Vec<LinkRule>) -> Self {
+        self.link_rules = rules;
+        self
+    }
+
+    /// Sets what programs may do with the clipboard through OSC 52.
+    pub fn clipboard_policy(mut self, policy: ClipboardPolicy) -> Self {
+        self.clipboard_policy = policy;
+        self
+    }
+
+    /// Enables the primary selection, which is on by default.
+    ///
+    /// Selected text is copied to it, and a middle-click pastes it, like in other
+    /// terminals on X11 and Wayland. Other platforms have no primary selection.
+    pub fn primary_selection(mut self, enabled: bool) -> Self {
+        self.primary_selection = enabled;
+        self
+    }
+
+    /// Replaces the rules for selecting URLs, paths and the like with a double-click.
+    ///
+    /// Extend [`SmartSelectionRule::defaults`] to keep the default rules.
+    /// Pass an empty list to always select words.
+    pub fn smart_selection_rules(mut self, rules: Vec<SmartSelectionRule>) -> Self {
+        self.smart_selection_rules = rules;
+        self
+    }
+
+    /// Sets the characters besides alphanumerics that belong to a word when
+    /// double-clicking, `/-+\~_.` by default.
+    pub fn word_chars(mut self, chars: impl Into<String>) -> Self {
+        self.word_chars = chars.into();
+        self
+    }
+
+    /// Sets how many lines are kept in the scrollback.
+    pub fn scrollback_size(mut self, lines: usize) -> Self {
+        self.scrollback_size = lines;
+        self.apply_scrollback_limit();
+        self
+    }
+
+    /// Limits the memory used by the scrollback to roughly `bytes`.
+    ///
+    /// The budget is converted into a line limit based on the width of the terminal,
+    /// so wider terminals keep fewer lines. The smaller of this and
+    /// [`Terminal::scrollback_size`] wins.
+    pub fn memory_budget(mut self, bytes: Option<usize>) -> Self {
+        self.memory_budget = bytes;
+        self.apply_scrollback_limit();
+        self
+    }
+
+    /// The number of lines the scrollback currently keeps, with the memory budget applied.
+    pub fn scrollback_limit(&self) -> usize {
+        self.config.scrollback_size()
+    }
+
+    /// Updates the limit wezterm_term trims the scrollback to.
+    ///
+    /// Lines above a lowered limit are dropped once the next line scrolls in.
+    fn apply_scrollback_limit(&self) {
+        let mut lines = self.scrollback_size;
+
+        if let Some(budget) = self.memory_budget {
+            let size = self.term.get_size();
+            let line_size = size_of::<Line>() + size.cols * size_of::<Cell>();
+            // the lines on the screen count towards the budget as well
+            let budget_lines = (budget / line_size).saturating_sub(size.rows);
+            lines = lines.min(budget_lines);
+        }
+
+        self.config.scrollback_size.store(lines, Ordering::Relaxed);
+    }
+
+    /// Removes all lines from the scrollback, keeping the screen.
+    pub fn clear_scrollback(&mut self) {
+        self.term.erase_scrollback();
+        self.scroll_offset = 0;
+        self.refresh_search();
+    }
+
+    /// Clears the visible screen, like other terminals do: the lines above the cursor
+    /// are scrolled into the scrollback and the cursor line, usually the prompt, moves to the top.
+    ///
+    /// The program isn't told, as anything but a shell would take a redraw request
+    /// as input. Send it a form feed (Ctrl+L) with [`Terminal::send_bytes`] if it
+    /// should redraw.
+    pub fn clear_screen(&mut self) {
+        let cursor = self.term.cursor_pos();
+        let rows = self.term.screen().physical_rows;
+
+        // line feeds on the last row push the lines above the cursor into the scrollback
+        let mut clear = format!("\x1b[{rows};1H{}", "\n".repeat(cursor.y.max(0) as usize));
+        if rows > 1 {
+            clear.push_str("\x1b[2;1H\x1b[J");
+        }
+        clear.push_str(&format!("\x1b[1;{}H", cursor.x + 1));
+
+        self.advance_bytes(clear);
+        self.scroll_offset = 0;
+    }
+
+    /// Resets the terminal modes, like the cursor visibility, scroll margins and
+    /// text attributes (DECSTR), without touching the screen contents.
+    pub fn soft_reset(&mut self) {
+        self.advance_bytes(b"\x1b[!p");
+    }
+
+    /// Resets the terminal to its initial state (RIS) and clears the scrollback,
+    /// e.g. after a program left it garbled.
+    ///
+    /// Like [`Terminal::clear_screen`], this doesn't ask the program to redraw.
+    pub fn hard_reset(&mut self) {
+        self.advance_bytes(b"\x1bc");
+        self.clear_scrollback();
+    }
+
     pub fn update(&mut self, message: Message) -> Task<Message> {
         match message {
             Message::Resize(size) => {
-                self.term.resize(size);
+                self.resize(size);
                 Task::none()
             }
             Message::KeyPress {
                 modified_key: key,
                 modifiers,
             } => {
-                if let Some((key, modifiers)) = transform_key(key, modifiers) {
-                    self.term.key_down(key, modifiers).unwrap();
-                }
+                self.press_key(key, modifiers);
                 Task::none()
             }
+            Message::Paste(text) => {
+                self.scroll_offset = 0;
+                self.term.send_paste(&text).unwrap();
+                Task::none()
+            }
+            Message::Action(action) => {
+                self.perform(action);
+                Task::none()
+            }
+            Message::FocusChanged(focused) => {
+                // reports the change to the application, if it enabled focus tracking (DECSET 1004)
+                self.term.focus_changed(focused);
+                Task::none()
+            }
+            Message::ScrollToRow(row) => {
+                self.scroll_row_into_view(row);
+                Task::none()
+            }
+            Message::OpenLink(_) | Message::ClipboardRequest(_) => Task::none(),
+            Message::AllowClipboardRequest(request) => request.perform(),
         }
     }
 
+    fn perform(&mut self, action: KeyAction) {
+        match action {
+            // the widget handles these, since it has access to the clipboard
+            KeyAction::Copy | KeyAction::Paste | KeyAction::CopyMode => {}
+            KeyAction::ScrollLines(lines) => self.scroll_by(lines),
+            KeyAction::ScrollPages(pages) => {
+                let rows = self.term.screen().physical_rows as isize;
+                self.scroll_by(pages * rows);
+            }
+            KeyAction::ScrollToTop => self.scroll_offset = self.max_scroll_offset(),
+            KeyAction::ScrollToBottom => self.scroll_offset = 0,
+            KeyAction::IncreaseFontSize => self.zoom = (self.zoom + ZOOM_STEP).min(MAX_ZOOM),
+            KeyAction::DecreaseFontSize => self.zoom = (self.zoom - ZOOM_STEP).max(MIN_ZOOM),
+            KeyAction::ResetFontSize => self.zoom = 1.0,
+            KeyAction::ClearScrollback => self.clear_scrollback(),
+            KeyAction::ClearScreen => self.clear_screen(),
+            KeyAction::SoftReset => self.soft_reset(),
+            KeyAction::HardReset => self.hard_reset(),
+            KeyAction::SendBytes(bytes) => self.send_bytes(bytes),
+            KeyAction::Emit(_) => {}
+        }
+    }
+
+    /// The text spans the widget draws for the viewport, one span per run of equal attributes.
+    pub(crate) fn spans<Font>(&self) -> Vec<iced::advanced::text::Span<'static, (), Font>> {
+        let screen = self.term.screen();
+        let palette = self.term.palette();
+        let viewport = self.viewport_phys_range();
+        let term_lines = screen.lines_in_phys_range(viewport.clone());
+
+        let top_row = screen.phys_to_stable_row_index(viewport.start);
+        let bottom_row = screen.phys_to_stable_row_index(viewport.end);
+        let current_match = self.current_match();
+        let visible_matches: Vec<&SearchMatch> = self
+            .search_matches()
+            .iter()
+            .filter(|found| found.end.row >= top_row && found.start.row < bottom_row)
+            .collect();
+
+        let span = |text: String, attrs: &CellAttributes, highlight: Option<Color>| {
+            let span = iced::advanced::text::Span::new(text)
+                .color_maybe(get_color(attrs.foreground(), &palette))
+                .background_maybe(get_color(attrs.background(), &palette));
+
+            match highlight {
+                Some(highlight) => span.color(Color::BLACK).background(highlight),
+                None => span,
+            }
+        };
+
+        let mut spans = Vec::new();
+        let mut current_text = String::new();
+        let mut current_attrs = CellAttributes::default();
+        let mut current_highlight = None;
+
+        for (line, row) in term_lines.iter().zip(top_row..) {
+            for cell in line.visible_cells() {
+                let point = GridPoint {
+                    row,
+                    col: cell.cell_index(),
+                };
+                let highlight = visible_matches
+                    .iter()
+                    .find(|found| found.contains(point))
+                    .map(|found| {
+                        if Some(*found) == current_match {
+                            SEARCH_CURRENT_COLOR
+                        } else {
+                            SEARCH_MATCH_COLOR
+                        }
+                    });
+
+                if cell.attrs() != &current_attrs || highlight != current_highlight {
+                    if !current_text.is_empty() {
+                        spans.push(span(
+                            current_text.clone(),
+                            &current_attrs,
+                            current_highlight,
+                        ));
+                        current_text.clear();
+                    }
+                    current_attrs = cell.attrs().clone();
+                    current_highlight = highlight;
+                }
+
+                current_text.push_str(cell.str());
+            }
+            current_text.push('\n');
+        }
+
+        if current_text.len() > 1 {
+            spans.push(span(current_text, &current_attrs, current_highlight));
+        }
+
+        spans
+    }
+
+    /// Encodes the key press with the [`KeyEncoding`] or, for most keys, with wezterm_term.
+    fn press_key(&mut self, key: keyboard::Key, modifiers: keyboard::Modifiers) {
+        if let Some(bytes) = self.key_encoding.encode(&key, modifiers) {
+            self.scroll_offset = 0;
+            self.write_bytes(&bytes);
+        } else if let Some((key, modifiers)) = transform_key(key, modifiers) {
+            self.scroll_offset = 0;
+            self.term.key_down(key, modifiers).unwrap();
+        }
+    }
+
+    /// Writes the text to the program, as if it was typed.
+    ///
+    /// Unlike [`Message::Paste`], the text is never wrapped in bracketed paste sequences.
+    pub fn send_text(&mut self, text: &str) {
+        self.send_bytes(text);
+    }
+
+    /// Writes raw bytes to the program.
+    pub fn send_bytes(&mut self, bytes: impl AsRef<[u8]>) {
+        self.scroll_offset = 0;
+        self.write_bytes(bytes.as_ref());
+    }
+
+    /// Presses the keys given in vim's notation, e.g. `git status<CR>` or `<C-c>`.
+    ///
+    /// The keys are encoded like key presses in the widget, so they respect the
+    /// [`KeyEncoding`] and the modes set by the program. Nothing is sent if the
+    /// notation is invalid.
+    pub fn send_keys(&mut self, keys: &str) -> Result<(), KeyNotationError> {
+        for (key, modifiers) in input::parse_keys(keys)? {
+            self.press_key(key, modifiers);
+        }
+        Ok(())
+    }
+
+    /// Writes bytes to the pty, after everything wezterm_term is still writing.
+    pub(crate) fn write_bytes(&mut self, bytes: &[u8]) {
+        self.writer.hold_back(bytes);
+        self.term.send_paste(WRITE_MARKER).unwrap();
+    }
+
+    /// Waits until everything sent to the program so far reached the writer,
+    /// or the timeout passed.
+    ///
+    /// The writer is called from a background thread, so tests checking what was
+    /// written have to wait for it. Only available with the `testing` feature.
+    #[cfg(feature = "testing")]
+    pub fn wait_until_written(&mut self, timeout: Duration) {
+        // a marker without bytes, which comes out after all earlier writes
+        self.write_bytes(&[]);
+        self.writer.wait_until_written(timeout);
+    }
+
+    fn scroll_by(&mut self, lines: isize) {
+        self.scroll_offset = self
+            .scroll_offset
+            .saturating_add_signed(-lines)
+            .min(self.max_scroll_offset());
+    }
+
+    fn max_scroll_offset(&self) -> usize {
+        let screen = self.term.screen();
+        screen.scrollback_rows() - screen.physical_rows
+    }
+
+    /// The physical rows currently shown, taking the scroll offset into account.
+    fn viewport_phys_range(&self) -> Range<usize> {
+        let screen = self.term.screen();
+        let offset = self.scroll_offset.min(self.max_scroll_offset());
+        let top = screen.scrollback_rows() - screen.physical_rows - offset;
+
+        top..top + screen.physical_rows
+    }
+
     pub fn advance_bytes<B: AsRef<[u8]>>(&mut self, bytes: B) {
+        let bytes = bytes.as_ref();
+        recording::record(&self.recorder, |recorder| recorder.output(bytes));
         self.term.advance_bytes(bytes);
+        osc52::process_output(
+            &mut self.osc_parser,
+            &self.clipboard_requests,
+            self.clipboard_policy,
+            bytes,
+        );
+        self.refresh_search();
+    }
+
+    /// Starts recording the session in the asciicast v2 format, replacing any running recording.
+    ///
+    /// The output, size changes and, if enabled, the input are written to `writer`
+    /// until [`Terminal::stop_recording`] is called. If writing fails, the recording stops.
+    pub fn start_recording(
+        &mut self,
+        writer: impl Write + Send + 'static,
+        options: RecordingOptions,
+    ) -> std::io::Result<()> {
+        let recorder = Recorder::start(Box::new(writer), &self.term.get_size(), options)?;
+
+        self.stop_recording();
+        *self.recorder.lock().unwrap() = Some(recorder);
+        Ok(())
+    }
+
+    pub fn stop_recording(&mut self) {
+        if let Some(recorder) = self.recorder.lock().unwrap().take() {
+            let _ = recorder.finish();
+        }
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recorder.lock().unwrap().is_some()
+    }
+
+    /// Finds all matches of the pattern on the screen and in the scrollback, oldest first.
+    ///
+    /// Fails if the pattern is too long to be compiled.
+    pub fn find(&self, pattern: &SearchPattern) -> Result<Vec<SearchMatch>, regex::Error> {
+        let screen = self.term.screen();
+        Ok(search::find(
+            screen,
+            &pattern.regex()?,
+            0..screen.scrollback_rows(),
+        ))
+    }
+
+    /// Highlights all matches of the pattern, replacing the previous search.
+    ///
+    /// The matches are kept up to date while new output arrives.
+    /// Use [`Terminal::search_next`] and [`Terminal::search_previous`] to scroll to them.
+    /// If the pattern is too long to be compiled, the previous search is kept.
+    pub fn search(&mut self, pattern: SearchPattern) -> Result<&[SearchMatch], regex::Error> {
+        self.search = Some(ActiveSearch::new(&self.term, &pattern)?);
+        self.search_version += 1;
+
+        Ok(self.search_matches())
+    }
+
+    /// Removes the search highlights.
+    pub fn clear_search(&mut self) {
+        if self.search.take().is_some() {
+            self.search_version += 1;
+        }
+    }
+
+    /// The matches of the current search, oldest first.
+    pub fn search_matches(&self) -> &[SearchMatch] {
+        self.search
+            .as_ref()
+            .map(|search| search.matches.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// The match last navigated to.
+    pub fn current_match(&self) -> Option<&SearchMatch> {
+        let search = self.search.as_ref()?;
+        search.matches.get(search.current?)
+    }
+
+    /// Scrolls to the next (newer) match, wrapping around at the end.
+    ///
+    /// Without a current match, this starts at the top of the viewport.
+    pub fn search_next(&mut self) -> Option<SearchMatch> {
+        let top = self
+            .term
+            .screen()
+            .phys_to_stable_row_index(self.viewport_phys_range().start);
+        let search = self.search.as_ref()?;
+        let count = search.matches.len();
+
+        let next = match search.current {
+            Some(current) => (current + 1) % count.max(1),
+            None => search
+                .matches
+                .iter()
+                .position(|found| found.start.row >= top)
+                .unwrap_or(0),
+        };
+
+        self.select_match(next)
+    }
+
+    /// Scrolls to the previous (older) match, wrapping around at the start.
+    ///
+    /// Without a current match, this starts at the bottom of the viewport.
+    pub fn search_previous(&mut self) -> Option<SearchMatch> {
+        let bottom = self
+            .term
+            .screen()
+            .phys_to_stable_row_index(self.viewport_phys_range().end);
+        let search = self.search.as_ref()?;
+        let count = search.matches.len();
+
+        let previous = match search.current {
+            Some(current) => (current + count - 1) % count.max(1),
+            None => search
+                .matches
+                .iter()
+                .rposition(|found| found.start.row < bottom)
+                .unwrap_or(count.saturating_sub(1)),
+        };
+
+        self.select_match(previous)
+    }
+
+    fn select_match(&mut self, index: usize) -> Option<SearchMatch> {
+        let search = self.search.as_mut()?;
+        let found = *search.matches.get(index)?;
+
+        search.current = Some(index);
+        self.search_version += 1;
+        self.scroll_to_row(found.start.row);
+
+        Some(found)
+    }
+
+    /// Updates the matches after the screen changed.
+    fn refresh_search(&mut self) {
+        if let Some(search) = &mut self.search {
+            search.refresh(&self.term);
+            self.search_version += 1;
+        }
+    }
+
+    /// Scrolls the row into the middle of the viewport, unless it is visible already.
+    fn scroll_to_row(&mut self, row: StableRowIndex) {
+        let screen = self.term.screen();
+        let Some(phys) = screen.stable_row_to_phys(row) else {
+            return;
+        };
+
+        if self.viewport_phys_range().contains(&phys) {
+            return;
+        }
+
+        let top = phys.saturating_sub(screen.physical_rows / 2);
+        self.scroll_offset = self.max_scroll_offset().saturating_sub(top);
+    }
+
+    /// Scrolls just enough to show the row, unlike [`Terminal::scroll_to_row`].
+    fn scroll_row_into_view(&mut self, row: StableRowIndex) {
+        let screen = self.term.screen();
+        let Some(phys) = screen.stable_row_to_phys(row) else {
+            return;
+        };
+        let viewport = self.viewport_phys_range();
+
+        let top = if phys < viewport.start {
+            phys
+        } else if phys >= viewport.end {
+            phys + 1 - screen.physical_rows
+        } else {
+            return;
+        };
+        self.scroll_offset = self.max_scroll_offset().saturating_sub(top);
+    }
+
+    /// The cells double-clicking the cell selects: the match of the first
+    /// [`SmartSelectionRule`] there, or else the word.
+    pub(crate) fn word_at(&self, point: GridPoint) -> Unit {
+        smart_selection::rule_match_at(&self.term, &self.smart_selection_rules, point)
+            .unwrap_or_else(|| selection::word_at(&self.term, point, &self.word_chars))
+    }
+
+    /// Where the copy mode cursor starts: at the cursor of the program if it is
+    /// visible, otherwise at the bottom of the viewport.
+    pub(crate) fn copy_mode_start(&self) -> GridPoint {
+        let screen = self.term.screen();
+        let viewport = self.viewport_phys_range();
+        let cursor = self.cursor_position();
+        let cursor_phys = screen.scrollback_rows() - screen.physical_rows + cursor.y as usize;
+
+        if viewport.contains(&cursor_phys) {
+            GridPoint {
+                row: screen.phys_to_stable_row_index(cursor_phys),
+                col: cursor.x,
+            }
+        } else {
+            GridPoint {
+                row: screen.phys_to_stable_row_index(viewport.end - 1),
+                col: 0,
+            }
+        }
+    }
+
+    /// Handles a key press in copy mode, returning what to do and the selection after it.
+    pub(crate) fn copy_mode_key(
+        &self,
+        copy_mode: &mut CopyMode,
+        key: &keyboard::Key,
+        modifiers: keyboard::Modifiers,
+    ) -> (CopyModeAction, Option<Selection>) {
+        let action = copy_mode.handle_key(self, key, modifiers, &self.word_chars);
+        (action, copy_mode.selection(&self.term))
+    }
+
+    /// The unit a click at the cell boundary (or, for words, the cell) selects.
+    pub(crate) fn selection_unit(&self, mode: SelectionMode, point: GridPoint) -> Unit {
+        match mode {
+            SelectionMode::Cell | SelectionMode::Block => (point, point),
+            SelectionMode::Word => self.word_at(point),
+            SelectionMode::Line => selection::line_at(&self.term, point),
+        }
+    }
+
+    #[cfg(feature = "testing")]
+    pub(crate) fn selection_text(&self, selection: &Selection) -> String {
+        selection.text(&self.term)
+    }
+
+    /// Handles the OSC 52 requests of the program like the widget does before drawing.
+    #[cfg(feature = "testing")]
+    pub(crate) fn handle_clipboard_requests(
+        &self,
+        clipboard: &mut dyn iced::advanced::Clipboard,
+        shell: &mut Shell<'_, Message>,
+    ) {
+        osc52::handle_requests(
+            &self.clipboard_requests,
+            self.clipboard_policy,
+            clipboard,
+            shell,
+        );
+    }
+
+    /// The OSC 8 hyperlink or detected link at the cell.
+    pub(crate) fn link_at(&self, point: GridPoint) -> Option<HoveredLink> {
+        links::link_at(
+            &self.term,
+            &self.link_rules,
+            self.viewport_phys_range(),
+            point,
+        )
     }
 
     pub fn get_title(&self) -> &str {
         self.term.get_title()
     }
 
+    /// The cursor position, relative to the top left of the live screen.
+    pub fn cursor_position(&self) -> CursorPosition {
+        self.term.cursor_pos()
+    }
+
+    /// The current size of the screen, in cells and pixels.
+    pub fn size(&self) -> TerminalSize {
+        self.term.get_size()
+    }
+
+    /// Whether the application switched to the alternate screen, like full screen programs do.
+    pub fn is_alt_screen_active(&self) -> bool {
+        self.term.is_alt_screen_active()
+    }
+
+    /// The rows of the live screen.
+    pub fn screen_rows(&self) -> Range<StableRowIndex> {
+        let screen = self.term.screen();
+        let end = screen.scrollback_rows();
+
+        screen.phys_to_stable_row_index(end - screen.physical_rows)
+            ..screen.phys_to_stable_row_index(end)
+    }
+
+    /// All rows, from the oldest line of the scrollback to the bottom of the screen.
+    pub fn all_rows(&self) -> Range<StableRowIndex> {
+        let screen = self.term.screen();
+
+        screen.phys_to_stable_row_index(0)
+            ..screen.phys_to_stable_row_index(screen.scrollback_rows())
+    }
+
+    /// The text of the rows, without trailing whitespace.
+    ///
+    /// Rows outside of [`Terminal::all_rows`] are skipped.
+    pub fn lines(&self, rows: Range<StableRowIndex>) -> Vec<String> {
+        let screen = self.term.screen();
+
+        screen
+            .lines_in_phys_range(screen.stable_range(&rows))
+            .iter()
+            .map(|line| line.as_str().trim_end().to_string())
+            .collect()
+    }
+
+    /// Serializes the rows with their colors and attributes.
+    ///
+    /// Pass [`Terminal::screen_rows`] for the visible screen or [`Terminal::all_rows`]
+    /// to include the scrollback.
+    pub fn export(&self, rows: Range<StableRowIndex>, format: ExportFormat) -> String {
+        export::export(&self.term, rows, format)
+    }
+
+    /// The text and attributes of a single cell.
+    ///
+    /// The columns after a wide character have no cell of their own.
+    pub fn cell(&self, point: GridPoint) -> Option<Cell> {
+        let screen = self.term.screen();
+        let phys = screen.stable_row_to_phys(point.row)?;
+        let line = screen.lines_in_phys_range(phys..phys + 1).pop()?;
+
+        line.get_cell(point.col).map(|cell| cell.as_cell())
+    }
+
     pub fn resize(&mut self, size: TerminalSize) {
-        self.term.resize(size)
+        recording::record(&self.recorder, |recorder| recorder.resize(&size));
+        self.term.resize(size);
+        self.apply_scrollback_limit();
+        self.refresh_search();
     }
 
     pub fn view<'a, Theme, Renderer>(&'a self) -> Element<'a, Message, Theme, Renderer>
     where
         Renderer: iced::advanced::text::Renderer<Font = iced::Font> + 'static,
+        Renderer: iced::advanced::image::Renderer<Handle = iced::advanced::image::Handle>,
         Theme: iced::widget::text::Catalog + 'static,
         Theme: iced::widget::container::Catalog,
         <Theme as iced::widget::text::Catalog>::Class<'static>:
@@ -196,21 +1128,20 @@ fn transform_key(
     }
 }
 
-fn get_color(color: ColorAttribute, palette: &ColorPalette) -> Option<iced::Color> {
+pub(crate) fn get_color(color: ColorAttribute, palette: &ColorPalette) -> Option<iced::Color> {
     match color {
         ColorAttribute::TrueColorWithPaletteFallback(srgba_tuple, _)
-        | ColorAttribute::TrueColorWithDefaultFallback(srgba_tuple) => {
-            let (r, g, b, a) = srgba_tuple.to_tuple_rgba();
-            Some(iced::Color::from_rgba(r, g, b, a))
-        }
-        ColorAttribute::PaletteIndex(index) => {
-            let (r, g, b, a) = palette.colors.0[index as usize].to_tuple_rgba();
-            Some(iced::Color::from_rgba(r, g, b, a))
-        }
+        | ColorAttribute::TrueColorWithDefaultFallback(srgba_tuple) => Some(to_color(srgba_tuple)),
+        ColorAttribute::PaletteIndex(index) => Some(to_color(palette.colors.0[index as usize])),
         ColorAttribute::Default => None,
     }
 }
 
+pub(crate) fn to_color(color: SrgbaTuple) -> iced::Color {
+    let (r, g, b, a) = color.to_tuple_rgba();
+    iced::Color::from_rgba(r, g, b, a)
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Id(iced::advanced::widget::Id);
 
@@ -246,6 +1177,89 @@ impl From<String> for Id {
     }
 }
 
+/// Produces a [`Task`] that focuses the [`Terminal`] with the given [`Id`].
+///
+/// All other focusable widgets are unfocused.
+pub fn focus<T>(id: impl Into<Id>) -> Task<T>
+where
+    T: Send + 'static,
+{
+    iced::advanced::widget::operate(operation::focusable::focus(id.into().0))
+}
+
+/// Produces a [`Task`] that unfocuses the [`Terminal`] with the given [`Id`].
+pub fn unfocus<T>(id: impl Into<Id>) -> Task<T>
+where
+    T: Send + 'static,
+{
+    struct Unfocus {
+        target: iced::advanced::widget::Id,
+    }
+
+    impl<T> Operation<T> for Unfocus {
+        fn focusable(
+            &mut self,
+            state: &mut dyn Focusable,
+            id: Option<&iced::advanced::widget::Id>,
+        ) {
+            if id == Some(&self.target) {
+                state.unfocus();
+            }
+        }
+
+        fn container(
+            &mut self,
+            _id: Option<&iced::advanced::widget::Id>,
+            _bounds: Rectangle,
+            operate_on_children: &mut dyn FnMut(&mut dyn Operation<T>),
+        ) {
+            operate_on_children(self);
+        }
+    }
+
+    iced::advanced::widget::operate(Unfocus {
+        target: id.into().0,
+    })
+}
+
+/// Produces a [`Task`] that returns whether the [`Terminal`] with the given [`Id`] is focused.
+pub fn is_focused(id: impl Into<Id>) -> Task<bool> {
+    struct IsFocused {
+        target: iced::advanced::widget::Id,
+        focused: bool,
+    }
+
+    impl Operation<bool> for IsFocused {
+        fn focusable(
+            &mut self,
+            state: &mut dyn Focusable,
+            id: Option<&iced::advanced::widget::Id>,
+        ) {
+            if id == Some(&self.target) {
+                self.focused = state.is_focused();
+            }
+        }
+
+        fn container(
+            &mut self,
+            _id: Option<&iced::advanced::widget::Id>,
+            _bounds: Rectangle,
+            operate_on_children: &mut dyn FnMut(&mut dyn Operation<bool>),
+        ) {
+            operate_on_children(self);
+        }
+
+        fn finish(&self) -> operation::Outcome<bool> {
+            operation::Outcome::Some(self.focused)
+        }
+    }
+
+    iced::advanced::widget::operate(IsFocused {
+        target: id.into().0,
+        focused: false,
+    })
+}
+
 struct TerminalWidget<'a, R: iced::advanced::text::Renderer> {
     id: Option<Id>,
     term: &'a Terminal,
@@ -268,20 +1282,140 @@ where
         self.id = id;
         self
     }
+
+    fn font_size(&self, renderer: &R) -> f32 {
+        renderer.default_size().0 * self.term.zoom
+    }
+
+    /// Returns the cell at a position relative to the widget, if there is one.
+    /// The link at a position relative to the widget.
+    fn link_under(&self, position: Point, font_size: f32) -> Option<HoveredLink> {
+        self.cell_at(position, font_size)
+            .and_then(|point| self.term.link_at(point))
+    }
+
+    fn cell_at(&self, position: Point, font_size: f32) -> Option<GridPoint> {
+        let screen = self.term.term.screen();
+
+        if position.x < 0.0 || position.y < 0.0 {
+            return None;
+        }
+
+        let col = (position.x / (font_size * CHAR_WIDTH)) as usize;
+        let row = (position.y / (font_size * LINE_HEIGHT)) as usize;
+
+        if col >= screen.physical_cols || row >= screen.physical_rows {
+            return None;
+        }
+
+        let phys = self.term.viewport_phys_range().start + row;
+
+        Some(GridPoint {
+            row: screen.phys_to_stable_row_index(phys),
+            col,
+        })
+    }
+
+    /// The cell, word or line at a position relative to the widget.
+    fn selection_unit(&self, mode: SelectionMode, position: Point, font_size: f32) -> Unit {
+        let point = self.grid_point(position, font_size);
+
+        // words are picked by the cell under the pointer, not the nearest cell boundary
+        let point = match mode {
+            SelectionMode::Word => self.cell_at(position, font_size).unwrap_or(point),
+            _ => point,
+        };
+
+        self.term.selection_unit(mode, point)
+    }
+
+    /// Converts a position relative to the widget into a grid cell.
+    ///
+    /// Positions outside of the widget are clamped to the visible screen.
+    fn grid_point(&self, position: Point, font_size: f32) -> GridPoint {
+        let screen = self.term.term.screen();
+
+        let col = (position.x / (font_size * CHAR_WIDTH)).round().max(0.0) as usize;
+        let row = (position.y / (font_size * LINE_HEIGHT)).max(0.0) as usize;
+
+        let phys = self.term.viewport_phys_range().start + row.min(screen.physical_rows - 1);
+
+        GridPoint {
+            row: screen.phys_to_stable_row_index(phys),
+            col: col.min(screen.physical_cols),
+        }
+    }
 }
 
 struct State<R: Renderer> {
     focused: bool,
+    /// The focus state last published as [`Message::FocusChanged`].
+    reported_focus: bool,
     paragraph: R::Paragraph,
     spans: Vec<iced::advanced::text::Span<'static, (), R::Font>>,
-    last_render_seqno: usize,
+    last_render_seqno: Option<usize>,
+    search_version: usize,
+    bounds: Size,
     cursor: CursorPosition,
+    /// The grapheme under the cursor, drawn inverted inside block cursors.
+    cursor_text: String,
+    cursor_color: Color,
+    cursor_text_color: Color,
+    /// The start of the current blink cycle, reset by key presses.
     last_cursor_blink: Instant,
+    cursor_blink_on: bool,
     now: Instant,
+    font_size: f32,
+    scroll_offset: usize,
+    selection: Option<Selection>,
+    /// Used to detect double and triple clicks.
+    last_click: Option<Click>,
+    selecting: bool,
+    copy_mode: Option<CopyMode>,
+    /// The last mouse position relative to the widget, to find the hovered link again
+    /// after scrolling or new output.
+    mouse_position: Option<Point>,
+    hovered_link: Option<HoveredLink>,
+    modifiers: keyboard::Modifiers,
+    images: Vec<ImagePlacement>,
+    image_cache: ImageCache,
+}
+
+impl<R: Renderer> State<R> {
+    fn restart_blink(&mut self) {
+        self.last_cursor_blink = Instant::now();
+        self.now = self.last_cursor_blink;
+        self.cursor_blink_on = true;
+    }
+
+    /// Publishes focus changes, including the ones caused by widget operations.
+    fn report_focus(&mut self, shell: &mut Shell<'_, Message>) {
+        if self.focused != self.reported_focus {
+            self.reported_focus = self.focused;
+
+            if self.focused {
+                self.restart_blink();
+            }
+
+            shell.publish(Message::FocusChanged(self.focused));
+        }
+    }
 }
 
-const CHAR_WIDTH: f32 = 0.6;
-const CURSOR_BLINK_INTERVAL_MILLIS: u128 = 500;
+pub(crate) const CHAR_WIDTH: f32 = 0.6;
+pub(crate) const LINE_HEIGHT: f32 = 1.3;
+const DEFAULT_CURSOR_BLINK_INTERVAL: Duration = Duration::from_millis(500);
+const DEFAULT_CURSOR_BLINK_TIMEOUT: Duration = Duration::from_secs(15);
+const DEFAULT_SCROLLBACK_SIZE: usize = 3500;
+const DEFAULT_WORD_CHARS: &str = "/-+\\~_.";
+const WHEEL_SCROLL_LINES: f32 = 3.0;
+const ZOOM_STEP: f32 = 0.1;
+const MIN_ZOOM: f32 = 0.5;
+const MAX_ZOOM: f32 = 3.0;
+const COPY_MODE_CURSOR_COLOR: Color = Color::from_rgba(1.0, 0.55, 0.1, 0.7);
+const SELECTION_COLOR: Color = Color::from_rgba(1.0, 1.0, 1.0, 0.3);
+const SEARCH_MATCH_COLOR: Color = Color::from_rgb(0.9, 0.8, 0.3);
+const SEARCH_CURRENT_COLOR: Color = Color::from_rgb(1.0, 0.55, 0.1);
 
 impl<Renderer> Focusable for State<Renderer>
 where
@@ -304,6 +1438,7 @@ impl<Theme, Renderer> iced::advanced::widget::Widget<Message, Theme, Renderer>
     for TerminalWidget<'_, Renderer>
 where
     Renderer: iced::advanced::text::Renderer,
+    Renderer: iced::advanced::image::Renderer<Handle = iced::advanced::image::Handle>,
     Renderer: 'static,
 {
     fn tag(&self) -> iced::advanced::widget::tree::Tag {
@@ -313,12 +1448,30 @@ where
     fn state(&self) -> iced::advanced::widget::tree::State {
         iced::advanced::widget::tree::State::new(State::<Renderer> {
             focused: false,
+            reported_focus: false,
             paragraph: Renderer::Paragraph::default(),
             spans: Vec::new(),
-            last_render_seqno: 0,
+            last_render_seqno: None,
+            search_version: 0,
+            bounds: Size::ZERO,
             cursor: CursorPosition::default(),
+            cursor_text: String::new(),
+            cursor_color: Color::WHITE,
+            cursor_text_color: Color::BLACK,
             last_cursor_blink: Instant::now(),
+            cursor_blink_on: true,
             now: Instant::now(),
+            font_size: 0.0,
+            scroll_offset: 0,
+            selection: None,
+            last_click: None,
+            selecting: false,
+            copy_mode: None,
+            mouse_position: None,
+            hovered_link: None,
+            modifiers: keyboard::Modifiers::default(),
+            images: Vec::new(),
+            image_cache: ImageCache::default(),
         })
     }
 
@@ -326,6 +1479,25 @@ where
         Size::new(Length::Fill, Length::Fill)
     }
 
+    fn mouse_interaction(
+        &self,
+        tree: &iced::advanced::widget::Tree,
+        layout: iced::advanced::Layout<'_>,
+        cursor: iced::advanced::mouse::Cursor,
+        _viewport: &iced::Rectangle,
+        _renderer: &Renderer,
+    ) -> mouse::Interaction {
+        let state = tree.state.downcast_ref::<State<Renderer>>();
+
+        if !cursor.is_over(layout.bounds()) {
+            mouse::Interaction::None
+        } else if state.hovered_link.is_some() {
+            mouse::Interaction::Pointer
+        } else {
+            mouse::Interaction::Text
+        }
+    }
+
     fn operate(
         &self,
         tree: &mut iced::advanced::widget::Tree,
@@ -347,58 +1519,54 @@ where
         let state = tree.state.downcast_mut::<State<Renderer>>();
         let term = &self.term.term;
         let current_seqno = term.current_seqno();
+        let font_size = self.font_size(renderer);
+        let scroll_offset = self.term.scroll_offset.min(self.term.max_scroll_offset());
 
-        if state.last_render_seqno != current_seqno {
-            let screen = term.screen();
+        // only rebuild the paragraph if something visible changed
+        let is_up_to_date = state.last_render_seqno == Some(current_seqno)
+            && state.scroll_offset == scroll_offset
+            && state.search_version == self.term.search_version
+            && state.font_size == font_size
+            && state.bounds == limits.max();
 
-            let range = screen.phys_range(&(0..screen.physical_rows as i64));
-            let term_lines = screen.lines_in_phys_range(range);
+        if !is_up_to_date {
+            state.last_render_seqno = Some(current_seqno);
+            state.search_version = self.term.search_version;
+            state.bounds = limits.max();
+
+            let screen = term.screen();
 
-            let mut current_text = String::new();
-            let mut current_attrs = CellAttributes::default();
-            state.spans.clear();
+            let term_lines = screen.lines_in_phys_range(self.term.viewport_phys_range());
 
             let palette = term.palette();
 
             state.cursor = term.cursor_pos();
-
-            for line in term_lines.iter() {
-                for cell in line.visible_cells() {
-                    if cell.attrs() != &current_attrs {
-                        if !current_text.is_empty() {
-                            let foreground = get_color(current_attrs.foreground(), &palette);
-                            let background = get_color(current_attrs.background(), &palette);
-
-                            let span = iced::advanced::text::Span::new(current_text.clone())
-                                .color_maybe(foreground)
-                                .background_maybe(background);
-
-                            state.spans.push(span);
-                            current_text.clear();
-                        }
-                        current_attrs = cell.attrs().clone();
-                    }
-
-                    current_text.push_str(cell.str());
-                }
-                current_text.push('\n');
-            }
-
-            if current_text.len() > 1 {
-                let foreground = get_color(current_attrs.foreground(), &palette);
-                let background = get_color(current_attrs.background(), &palette);
-
-                let span = iced::advanced::text::Span::new(current_text)
-                    .color_maybe(foreground)
-                    .background_maybe(background);
-
-                state.spans.push(span);
-            }
+            state.cursor_text = term_lines
+                .get(state.cursor.y as usize)
+                .and_then(|line| line.get_cell(state.cursor.x))
+                .map(|cell| cell.str().to_string())
+                .unwrap_or_default();
+            state.cursor_color = to_color(palette.cursor_bg);
+            state.cursor_text_color = to_color(palette.cursor_fg);
+            state.font_size = font_size;
+            state.scroll_offset = scroll_offset;
+            // the text under the mouse may have changed
+            state.hovered_link = state
+                .mouse_position
+                .and_then(|position| self.link_under(position, font_size));
+            state.images = images::placements(
+                &term_lines,
+                &mut state.image_cache,
+                font_size * CHAR_WIDTH,
+                font_size * LINE_HEIGHT,
+            );
+
+            state.spans = self.term.spans();
 
             let text = Text {
                 content: state.spans.as_ref(),
                 bounds: limits.max(),
-                size: renderer.default_size(),
+                size: Pixels(font_size),
                 line_height: LineHeight::default(),
                 font: self.font,
                 horizontal_alignment: Horizontal::Left,
@@ -418,9 +1586,9 @@ where
         tree: &mut iced::advanced::widget::Tree,
         event: iced::Event,
         layout: iced::advanced::Layout<'_>,
-        _cursor: iced::advanced::mouse::Cursor,
+        cursor: iced::advanced::mouse::Cursor,
         renderer: &Renderer,
-        _clipboard: &mut dyn iced::advanced::Clipboard,
+        clipboard: &mut dyn iced::advanced::Clipboard,
         shell: &mut Shell<'_, Message>,
         _viewport: &iced::Rectangle,
     ) -> iced::advanced::graphics::core::event::Status {
@@ -431,14 +1599,15 @@ where
 
                 let widget_width = layout.bounds().width;
                 let widget_height = layout.bounds().height;
-                let line_height = renderer.default_size().0;
+                let line_height = self.font_size(renderer);
                 let char_width = line_height * CHAR_WIDTH;
 
                 let target_line_count = (0.77 * widget_height / line_height) as usize;
                 let target_col_count = (widget_width / char_width) as usize;
 
-                if screen.physical_rows != target_line_count
-                    || screen.physical_cols != target_col_count
+                if !self.term.fixed_size
+                    && (screen.physical_rows != target_line_count
+                        || screen.physical_cols != target_col_count)
                 {
                     let size = TerminalSize {
                         rows: target_line_count,
@@ -450,13 +1619,40 @@ where
                     shell.publish(Message::Resize(size));
                 }
 
+                osc52::handle_requests(
+                    &self.term.clipboard_requests,
+                    self.term.clipboard_policy,
+                    clipboard,
+                    shell,
+                );
+
                 // handle blinking cursor
                 let state = tree.state.downcast_mut::<State<Renderer>>();
-                if state.focused {
-                    state.now = now;
-                    let millis_until_redraw = CURSOR_BLINK_INTERVAL_MILLIS
-                        - (now - state.last_cursor_blink).as_millis()
-                            % CURSOR_BLINK_INTERVAL_MILLIS;
+                state.report_focus(shell);
+
+                state.now = now;
+                state.cursor_blink_on = true;
+
+                // only keep redrawing while the cursor actually blinks,
+                // read-only terminals don't draw it at all
+                let blink_interval = self.term.cursor_blink_interval.filter(|_| {
+                    !self.term.read_only
+                        && state.focused
+                        && state.scroll_offset == 0
+                        && state.cursor.visibility == CursorVisibility::Visible
+                        && cursor_blinks(state.cursor.shape)
+                });
+                let since_blink_start = now.saturating_duration_since(state.last_cursor_blink);
+
+                if let Some(interval) = blink_interval
+                    && since_blink_start < self.term.cursor_blink_timeout
+                {
+                    let interval = interval.as_millis().max(1);
+                    let elapsed = since_blink_start.as_millis();
+
+                    state.cursor_blink_on = (elapsed / interval).is_multiple_of(2);
+
+                    let millis_until_redraw = interval - elapsed % interval;
 
                     shell.request_redraw(RedrawRequest::At(
                         now + Duration::from_millis(millis_until_redraw as u64),
@@ -465,32 +1661,240 @@ where
 
                 iced::advanced::graphics::core::event::Status::Ignored
             }
-            iced::Event::Mouse(iced::mouse::Event::ButtonPressed(_))
-            | iced::Event::Touch(iced::touch::Event::FingerPressed { .. }) => {
+            iced::Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left))
+                if cursor.is_over(layout.bounds()) =>
+            {
+                let state = tree.state.downcast_mut::<State<Renderer>>();
+                let position = cursor.position_in(layout.bounds()).unwrap_or(Point::ORIGIN);
+                let font_size = self.font_size(renderer);
+
+                state.focused = true;
+                state.report_focus(shell);
+
+                if state.modifiers.control()
+                    && let Some(link) = self.link_under(position, font_size)
+                {
+                    shell.publish(Message::OpenLink(link.link));
+
+                    return iced::advanced::graphics::core::event::Status::Captured;
+                }
+
+                let click = Click::new(position, mouse::Button::Left, state.last_click);
+                state.last_click = Some(click);
+
+                let mode = match click.kind() {
+                    click::Kind::Single if state.modifiers.alt() => SelectionMode::Block,
+                    click::Kind::Single => SelectionMode::Cell,
+                    click::Kind::Double => SelectionMode::Word,
+                    click::Kind::Triple => SelectionMode::Line,
+                };
+
+                match &mut state.selection {
+                    Some(selection)
+                        if state.modifiers.shift()
+                            && matches!(click.kind(), click::Kind::Single) =>
+                    {
+                        selection.extend(self.selection_unit(
+                            selection.mode(),
+                            position,
+                            font_size,
+                        ));
+                    }
+                    _ => {
+                        state.selection = Some(Selection::new(
+                            mode,
+                            self.selection_unit(mode, position, font_size),
+                        ));
+                    }
+                }
+                state.selecting = true;
+
+                iced::advanced::graphics::core::event::Status::Captured
+            }
+            iced::Event::Mouse(mouse::Event::CursorMoved { position }) => {
+                let state = tree.state.downcast_mut::<State<Renderer>>();
+                let font_size = self.font_size(renderer);
+                let relative = position - layout.position();
+                let relative = Point::new(relative.x, relative.y);
+
+                state.mouse_position = Some(relative);
+                state.hovered_link = self.link_under(relative, font_size);
+
+                if !state.selecting {
+                    return iced::advanced::graphics::core::event::Status::Ignored;
+                }
+
+                if let Some(selection) = &mut state.selection {
+                    selection.extend(self.selection_unit(selection.mode(), relative, font_size));
+                }
+
+                iced::advanced::graphics::core::event::Status::Captured
+            }
+            iced::Event::Mouse(mouse::Event::WheelScrolled { delta })
+                if cursor.is_over(layout.bounds()) =>
+            {
+                // the alternate screen has no scrollback
+                if self.term.term.is_alt_screen_active() {
+                    return iced::advanced::graphics::core::event::Status::Ignored;
+                }
+
+                let lines = match delta {
+                    mouse::ScrollDelta::Lines { y, .. } => y * WHEEL_SCROLL_LINES,
+                    mouse::ScrollDelta::Pixels { y, .. } => {
+                        y / (self.font_size(renderer) * LINE_HEIGHT)
+                    }
+                };
+
+                // scrolling the wheel up (positive) moves into the scrollback
+                let lines = -lines.round() as isize;
+                if lines != 0 {
+                    shell.publish(Message::Action(KeyAction::ScrollLines(lines)));
+                }
+
+                iced::advanced::graphics::core::event::Status::Captured
+            }
+            iced::Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) => {
+                let state = tree.state.downcast_mut::<State<Renderer>>();
+                state.modifiers = modifiers;
+
+                iced::advanced::graphics::core::event::Status::Ignored
+            }
+            iced::Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                let state = tree.state.downcast_mut::<State<Renderer>>();
+
+                if state.selecting
+                    && self.term.primary_selection
+                    && let Some(selection) =
+                        state.selection.filter(|selection| !selection.is_empty())
+                {
+                    clipboard.write(clipboard::Kind::Primary, selection.text(&self.term.term));
+                }
+                state.selecting = false;
+
+                iced::advanced::graphics::core::event::Status::Ignored
+            }
+            iced::Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Middle))
+                if self.term.primary_selection && cursor.is_over(layout.bounds()) =>
+            {
                 let state = tree.state.downcast_mut::<State<Renderer>>();
 
                 state.focused = true;
+                state.report_focus(shell);
+
+                if !self.term.read_only
+                    && let Some(text) = clipboard.read(clipboard::Kind::Primary)
+                {
+                    shell.publish(Message::Paste(text));
+                }
 
                 iced::advanced::graphics::core::event::Status::Captured
             }
+            iced::Event::Mouse(iced::mouse::Event::ButtonPressed(_)) => {
+                let state = tree.state.downcast_mut::<State<Renderer>>();
+
+                state.focused = cursor.is_over(layout.bounds());
+                state.report_focus(shell);
+
+                if state.focused {
+                    iced::advanced::graphics::core::event::Status::Captured
+                } else {
+                    iced::advanced::graphics::core::event::Status::Ignored
+                }
+            }
+            iced::Event::Touch(iced::touch::Event::FingerPressed { position, .. }) => {
+                let state = tree.state.downcast_mut::<State<Renderer>>();
+
+                state.focused = layout.bounds().contains(position);
+                state.report_focus(shell);
+
+                if state.focused {
+                    iced::advanced::graphics::core::event::Status::Captured
+                } else {
+                    iced::advanced::graphics::core::event::Status::Ignored
+                }
+            }
             iced::Event::Keyboard(iced::keyboard::Event::KeyPressed {
+                key,
                 modified_key,
                 modifiers,
                 ..
             }) => {
                 let state = tree.state.downcast_mut::<State<Renderer>>();
 
-                if state.focused {
-                    let message = Message::KeyPress {
-                        modified_key: modified_key,
-                        modifiers: modifiers,
-                    };
-                    shell.publish(message);
+                if !state.focused {
+                    return iced::advanced::graphics::core::event::Status::Ignored;
+                }
 
-                    iced::advanced::graphics::core::event::Status::Captured
-                } else {
-                    iced::advanced::graphics::core::event::Status::Ignored
+                // restart the blink phase, so the cursor stays visible while typing
+                state.restart_blink();
+
+                if let Some(copy_mode) = &mut state.copy_mode {
+                    let (action, selection) =
+                        self.term.copy_mode_key(copy_mode, &modified_key, modifiers);
+
+                    match action {
+                        CopyModeAction::Update => {
+                            shell.publish(Message::ScrollToRow(copy_mode.cursor.row));
+                            state.selection = selection;
+                        }
+                        CopyModeAction::Yank | CopyModeAction::Exit => {
+                            if action == CopyModeAction::Yank
+                                && let Some(selection) = selection
+                            {
+                                let text = selection.text(&self.term.term);
+                                if self.term.primary_selection {
+                                    clipboard.write(clipboard::Kind::Primary, text.clone());
+                                }
+                                clipboard.write(clipboard::Kind::Standard, text);
+                            }
+
+                            state.copy_mode = None;
+                            state.selection = None;
+                            shell.publish(Message::Action(KeyAction::ScrollToBottom));
+                        }
+                    }
+
+                    return iced::advanced::graphics::core::event::Status::Captured;
+                }
+
+                if let Some(action) = self.term.key_bindings.find(&key, modifiers) {
+                    match action {
+                        KeyAction::Copy => {
+                            if let Some(selection) =
+                                state.selection.filter(|selection| !selection.is_empty())
+                            {
+                                clipboard.write(
+                                    clipboard::Kind::Standard,
+                                    selection.text(&self.term.term),
+                                );
+                            }
+                        }
+                        KeyAction::Paste => {
+                            if let Some(text) = clipboard.read(clipboard::Kind::Standard) {
+                                shell.publish(Message::Paste(text));
+                            }
+                        }
+                        KeyAction::CopyMode => {
+                            state.copy_mode = Some(CopyMode::new(self.term.copy_mode_start()));
+                            state.selection = None;
+                        }
+                        action => shell.publish(Message::Action(action.clone())),
+                    }
+
+                    return iced::advanced::graphics::core::event::Status::Captured;
                 }
+
+                if self.term.read_only {
+                    return iced::advanced::graphics::core::event::Status::Ignored;
+                }
+
+                let message = Message::KeyPress {
+                    modified_key,
+                    modifiers,
+                };
+                shell.publish(message);
+
+                iced::advanced::graphics::core::event::Status::Captured
             }
             _ => iced::advanced::graphics::core::event::Status::Ignored,
         }
@@ -536,53 +1940,301 @@ where
             }
         }
 
+        if let Some(selection) = &state.selection {
+            draw_selection(renderer, self.term, selection, state.font_size, translation);
+        }
+
+        if let Some(copy_mode) = &state.copy_mode {
+            draw_copy_mode_cursor(renderer, self.term, copy_mode, state.font_size, translation);
+        }
+
+        draw_images(
+            renderer,
+            state.images.iter().filter(|image| image.z_index < 0),
+            translation,
+            bounds,
+        );
+
         renderer.fill_paragraph(&state.paragraph, bounds.position(), Color::WHITE, bounds);
 
-        draw_cursor(renderer, &state, translation);
+        draw_images(
+            renderer,
+            state.images.iter().filter(|image| image.z_index >= 0),
+            translation,
+            bounds,
+        );
+
+        if let Some(link) = &state.hovered_link {
+            draw_link_underline(renderer, self.term, link, state.font_size, translation);
+        }
+
+        if !self.term.read_only {
+            draw_cursor(renderer, state, self.font, translation, bounds);
+        }
+
+        if let Some(input) = state
+            .copy_mode
+            .as_ref()
+            .and_then(|copy_mode| copy_mode.search_input.as_ref())
+        {
+            draw_search_prompt(renderer, input, state.font_size, self.font, layout.bounds());
+        }
+    }
+}
+
+fn draw_images<'a, Renderer>(
+    renderer: &mut Renderer,
+    images: impl Iterator<Item = &'a ImagePlacement>,
+    translation: iced::Vector,
+    bounds: Rectangle,
+) where
+    Renderer: iced::advanced::image::Renderer<Handle = iced::advanced::image::Handle>,
+{
+    for image in images {
+        let Some(clip) = (image.clip + translation).intersection(&bounds) else {
+            continue;
+        };
+
+        renderer.with_layer(clip, |renderer| {
+            renderer.draw_image(
+                iced::advanced::image::Image::new(image.handle.clone()),
+                image.bounds + translation,
+            );
+        });
+    }
+}
+
+fn draw_selection<Renderer>(
+    renderer: &mut Renderer,
+    term: &Terminal,
+    selection: &Selection,
+    font_size: f32,
+    translation: iced::Vector,
+) where
+    Renderer: iced::advanced::text::Renderer,
+{
+    let screen = term.term.screen();
+    let char_width = font_size * CHAR_WIDTH;
+    let line_height = font_size * LINE_HEIGHT;
+
+    for (row, phys) in term.viewport_phys_range().enumerate() {
+        let stable_row = screen.phys_to_stable_row_index(phys);
+
+        let Some(cols) = selection.cols_in_row(stable_row, screen.physical_cols) else {
+            continue;
+        };
+
+        let bounds = Rectangle::new(
+            Point::new(cols.start as f32 * char_width, row as f32 * line_height),
+            Size::new(cols.len() as f32 * char_width, line_height),
+        );
+
+        renderer.fill_quad(
+            Quad {
+                bounds: bounds + translation,
+                ..Default::default()
+            },
+            SELECTION_COLOR,
+        );
+    }
+}
+
+fn draw_copy_mode_cursor<Renderer>(
+    renderer: &mut Renderer,
+    term: &Terminal,
+    copy_mode: &CopyMode,
+    font_size: f32,
+    translation: iced::Vector,
+) where
+    Renderer: iced::advanced::text::Renderer,
+{
+    let screen = term.term.screen();
+    let viewport = term.viewport_phys_range();
+
+    let Some(phys) = screen
+        .stable_row_to_phys(copy_mode.cursor.row)
+        .filter(|phys| viewport.contains(phys))
+    else {
+        return;
+    };
+
+    let bounds = Rectangle::new(
+        Point::new(
+            copy_mode.cursor.col as f32 * font_size * CHAR_WIDTH,
+            (phys - viewport.start) as f32 * font_size * LINE_HEIGHT,
+        ),
+        Size::new(font_size * CHAR_WIDTH, font_size * LINE_HEIGHT),
+    );
+
+    renderer.fill_quad(
+        Quad {
+            bounds: bounds + translation,
+            ..Default::default()
+        },
+        COPY_MODE_CURSOR_COLOR,
+    );
+}
+
+/// The query typed after `/` in copy mode, shown over the bottom line.
+fn draw_search_prompt<Renderer>(
+    renderer: &mut Renderer,
+    input: &str,
+    font_size: f32,
+    font: Renderer::Font,
+    widget_bounds: Rectangle,
+) where
+    Renderer: iced::advanced::text::Renderer,
+{
+    let line_height = font_size * LINE_HEIGHT;
+    let bounds = Rectangle::new(
+        Point::new(
+            widget_bounds.x,
+            widget_bounds.y + widget_bounds.height - line_height,
+        ),
+        Size::new(widget_bounds.width, line_height),
+    );
+
+    renderer.fill_quad(
+        Quad {
+            bounds,
+            ..Default::default()
+        },
+        Color::BLACK,
+    );
+
+    renderer.fill_text(
+        Text {
+            content: format!("/{input}"),
+            bounds: bounds.size(),
+            size: Pixels(font_size),
+            line_height: LineHeight::default(),
+            font,
+            horizontal_alignment: Horizontal::Left,
+            vertical_alignment: Vertical::Top,
+            shaping: Shaping::Advanced,
+            wrapping: Wrapping::None,
+        },
+        bounds.position(),
+        Color::WHITE,
+        widget_bounds,
+    );
+}
+
+fn draw_link_underline<Renderer>(
+    renderer: &mut Renderer,
+    term: &Terminal,
+    link: &HoveredLink,
+    font_size: f32,
+    translation: iced::Vector,
+) where
+    Renderer: iced::advanced::text::Renderer,
+{
+    let screen = term.term.screen();
+    let viewport = term.viewport_phys_range();
+    let char_width = font_size * CHAR_WIDTH;
+    let line_height = font_size * LINE_HEIGHT;
+
+    for (row, cols) in &link.cells {
+        let Some(phys) = screen
+            .stable_row_to_phys(*row)
+            .filter(|phys| viewport.contains(phys))
+        else {
+            continue;
+        };
+
+        let bounds = Rectangle::new(
+            Point::new(
+                cols.start as f32 * char_width,
+                (phys - viewport.start) as f32 * line_height + font_size * 1.2,
+            ),
+            Size::new(cols.len() as f32 * char_width, 1.0),
+        );
+
+        renderer.fill_quad(
+            Quad {
+                bounds: bounds + translation,
+                ..Default::default()
+            },
+            Color::WHITE,
+        );
     }
 }
 
+fn cursor_blinks(shape: CursorShape) -> bool {
+    matches!(
+        shape,
+        CursorShape::Default
+            | CursorShape::BlinkingBlock
+            | CursorShape::BlinkingUnderline
+            | CursorShape::BlinkingBar
+    )
+}
+
 fn draw_cursor<Renderer>(
     renderer: &mut Renderer,
     state: &State<Renderer>,
+    font: Renderer::Font,
     translation: iced::Vector,
+    clip_bounds: Rectangle,
 ) where
     Renderer: iced::advanced::text::Renderer,
 {
+    // the cursor belongs to the live screen, so it is hidden while scrolled back
+    // the blink phase is only updated while the cursor is allowed to blink
     let is_cursor_visible = state.cursor.visibility == CursorVisibility::Visible
-        && ((state.now - state.last_cursor_blink).as_millis() / CURSOR_BLINK_INTERVAL_MILLIS) % 2
-            == 0;
+        && state.scroll_offset == 0
+        && (!state.focused || state.cursor_blink_on);
 
     if !is_cursor_visible {
         return;
     }
 
+    let font_size = state.font_size;
+
     let base_cursor_position = Point::new(
-        state.cursor.x as f32 * renderer.default_size().0 * CHAR_WIDTH,
-        state.cursor.y as f32 * renderer.default_size().0 * 1.3,
+        state.cursor.x as f32 * font_size * CHAR_WIDTH,
+        state.cursor.y as f32 * font_size * LINE_HEIGHT,
     );
 
     let padding = 1.0;
 
+    let block_bounds = Rectangle::new(
+        base_cursor_position + translation + Vector::new(padding, padding),
+        Size::new(
+            font_size * CHAR_WIDTH - padding,
+            font_size * LINE_HEIGHT - padding,
+        ),
+    );
+
+    if !state.focused {
+        // unfocused terminals show a hollow block, regardless of the shape
+        renderer.fill_quad(
+            Quad {
+                bounds: block_bounds,
+                border: Border {
+                    color: state.cursor_color,
+                    width: 1.0,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            Color::TRANSPARENT,
+        );
+
+        return;
+    }
+
     let cursor_bounds = match state.cursor.shape {
         CursorShape::BlinkingUnderline | CursorShape::SteadyUnderline | CursorShape::Default => {
             Rectangle::new(
-                base_cursor_position
-                    + translation
-                    + Vector::new(0.0, renderer.default_size().0 * 1.2),
-                Size::new(renderer.default_size().0 * CHAR_WIDTH, 1.0),
+                base_cursor_position + translation + Vector::new(0.0, font_size * 1.2),
+                Size::new(font_size * CHAR_WIDTH, 1.0),
             )
         }
-        CursorShape::BlinkingBlock | CursorShape::SteadyBlock => Rectangle::new(
-            base_cursor_position + translation + Vector::new(padding, padding),
-            Size::new(
-                renderer.default_size().0 * CHAR_WIDTH - padding,
-                renderer.default_size().0 * 1.3 - padding,
-            ),
-        ),
+        CursorShape::BlinkingBlock | CursorShape::SteadyBlock => block_bounds,
         CursorShape::BlinkingBar | CursorShape::SteadyBar => Rectangle::new(
             base_cursor_position + translation + Vector::new(padding, padding),
-            Size::new(1.0, renderer.default_size().0 * 1.3 - padding),
+            Size::new(1.0, font_size * LINE_HEIGHT - padding),
         ),
     };
 
@@ -592,6 +2244,31 @@ fn draw_cursor<Renderer>(
             border: Border::default(),
             ..Default::default()
         },
-        Color::WHITE,
+        state.cursor_color,
+    );
+
+    // a block cursor hides the character below, so it is drawn again in the inverted color
+    let is_block = matches!(
+        state.cursor.shape,
+        CursorShape::BlinkingBlock | CursorShape::SteadyBlock
     );
+
+    if is_block && !state.cursor_text.trim().is_empty() {
+        renderer.fill_text(
+            Text {
+                content: state.cursor_text.clone(),
+                bounds: Size::new(f32::INFINITY, f32::INFINITY),
+                size: Pixels(font_size),
+                line_height: LineHeight::default(),
+                font,
+                horizontal_alignment: Horizontal::Left,
+                vertical_alignment: Vertical::Top,
+                shaping: Shaping::Advanced,
+                wrapping: Wrapping::None,
+            },
+            base_cursor_position + translation,
+            state.cursor_text_color,
+            clip_bounds,
+        );
+    }
 }
@@ -0,0 +1,329 @@
+use std::{
+    fmt::Write as _,
+    io::Write,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use iced::{
+    advanced::{Clipboard, Shell},
+    keyboard::{Key, Modifiers},
+};
+use termwiz::{
+    cell::{CellAttributes, Intensity, Underline},
+    color::ColorAttribute,
+};
+
+use crate::{
+    GridPoint, KeyAction, Link, Message, Terminal, TerminalSize,
+    copy_mode::{CopyMode, CopyModeAction},
+    selection::{Selection, SelectionMode},
+};
+
+/// A [`Terminal`] without a window, for testing how output and input are handled.
+///
+/// Everything the terminal writes is captured, and the screen can be compared
+/// against plain text or attribute snapshots.
+pub struct TestTerminal {
+    terminal: Terminal,
+    input: CaptureWriter,
+    /// The mouse or copy mode selection, kept in the widget state by the real widget.
+    selection: Option<Selection>,
+    copy_mode: Option<CopyMode>,
+}
+
+/// A writer keeping everything written to it, e.g. the input of a [`Terminal`]
+/// or a recording.
+#[derive(Debug, Clone, Default)]
+pub struct CaptureWriter(Arc<Mutex<Vec<u8>>>);
+
+impl CaptureWriter {
+    /// Everything written so far.
+    pub fn contents(&self) -> Vec<u8> {
+        self.0.lock().unwrap().clone()
+    }
+
+    /// Returns everything written since the last call.
+    pub fn take(&self) -> Vec<u8> {
+        std::mem::take(&mut *self.0.lock().unwrap())
+    }
+}
+
+impl Write for CaptureWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl TestTerminal {
+    pub fn new(rows: u16, cols: u16) -> Self {
+        let input = CaptureWriter::default();
+        let terminal = Terminal::new(rows, cols, Box::new(input.clone()));
+
+        Self {
+            terminal,
+            input,
+            selection: None,
+            copy_mode: None,
+        }
+    }
+
+    /// Applies builder methods to the terminal, e.g. `|terminal| terminal.key_encoding(encoding)`.
+    pub fn configure(mut self, configure: impl FnOnce(Terminal) -> Terminal) -> Self {
+        self.terminal = configure(self.terminal);
+        self
+    }
+
+    pub fn terminal(&self) -> &Terminal {
+        &self.terminal
+    }
+
+    pub fn terminal_mut(&mut self) -> &mut Terminal {
+        &mut self.terminal
+    }
+
+    /// Feeds output of the program into the terminal.
+    pub fn feed(&mut self, bytes: impl AsRef<[u8]>) {
+        self.terminal.advance_bytes(bytes);
+    }
+
+    /// Simulates a key press, as the widget would publish it.
+    ///
+    /// `key` is the key with the modifiers (except Ctrl) applied, e.g. `"A"` with Shift.
+    pub fn key_press(&mut self, key: Key, modifiers: Modifiers) {
+        let _ = self.terminal.update(Message::KeyPress {
+            modified_key: key,
+            modifiers,
+        });
+    }
+
+    /// Simulates typing the text, one key press per character.
+    pub fn type_text(&mut self, text: &str) {
+        for c in text.chars() {
+            self.key_press(Key::Character(c.to_string().into()), Modifiers::empty());
+        }
+    }
+
+    /// Simulates the widget being resized to the given amount of cells.
+    pub fn resize(&mut self, rows: usize, cols: usize) {
+        let _ = self.terminal.update(Message::Resize(TerminalSize {
+            rows,
+            cols,
+            ..Default::default()
+        }));
+    }
+
+    /// Returns everything the terminal wrote since the last call.
+    ///
+    /// wezterm_term writes key presses from a background thread, so this first waits
+    /// up to `timeout` until it wrote all of them.
+    pub fn take_input(&mut self, timeout: Duration) -> Vec<u8> {
+        self.terminal.wait_until_written(timeout);
+        self.input.take()
+    }
+
+    /// The text of the visible screen, one line per row, without trailing whitespace.
+    pub fn snapshot(&self) -> String {
+        self.terminal.lines(self.terminal.screen_rows()).join("\n")
+    }
+
+    /// Like [`TestTerminal::snapshot`], but runs of cells with non-default
+    /// attributes are wrapped in tags, e.g. `<bold fg=PaletteIndex(1)>error</>`.
+    pub fn attribute_snapshot(&self) -> String {
+        let rows = self.terminal.screen_rows();
+        let cols = self.terminal.size().cols;
+        let mut lines = Vec::new();
+
+        for row in rows {
+            let mut line = String::new();
+            let mut current = String::new();
+
+            for col in 0..cols {
+                let Some(cell) = self.terminal.cell(GridPoint { row, col }) else {
+                    continue;
+                };
+
+                let attrs = describe(cell.attrs());
+                if attrs != current {
+                    if !current.is_empty() {
+                        line.push_str("</>");
+                    }
+                    if !attrs.is_empty() {
+                        write!(line, "<{attrs}>").unwrap();
+                    }
+                    current = attrs;
+                }
+
+                line.push_str(cell.str());
+            }
+
+            // trailing whitespace only matters if it has attributes, e.g. a background color
+            if current.is_empty() {
+                lines.push(line.trim_end().to_string());
+            } else {
+                lines.push(line + "</>");
+            }
+        }
+
+        lines.join("\n")
+    }
+
+    /// The text double-clicking the cell would select, following the
+    /// [`SmartSelectionRule`](crate::SmartSelectionRule)s.
+    pub fn word_selection_at(&self, point: GridPoint) -> String {
+        let unit = self.terminal.word_at(point);
+        self.terminal
+            .selection_text(&Selection::new(SelectionMode::Word, unit))
+    }
+
+    /// Simulates pressing the left mouse button at the cell boundary `point`, as the
+    /// `clicks`-th click in a row: a double-click selects words, a triple-click lines.
+    ///
+    /// Like in the widget, Alt starts a block selection and Shift extends the current one.
+    pub fn click(&mut self, point: GridPoint, clicks: usize, modifiers: Modifiers) {
+        let mode = match clicks {
+            0 | 1 if modifiers.alt() => SelectionMode::Block,
+            0 | 1 => SelectionMode::Cell,
+            2 => SelectionMode::Word,
+            _ => SelectionMode::Line,
+        };
+
+        match &mut self.selection {
+            Some(selection) if modifiers.shift() && clicks <= 1 => {
+                selection.extend(self.terminal.selection_unit(selection.mode(), point));
+            }
+            _ => {
+                self.selection = Some(Selection::new(
+                    mode,
+                    self.terminal.selection_unit(mode, point),
+                ));
+            }
+        }
+    }
+
+    /// Simulates dragging the mouse to `point` after [`TestTerminal::click`].
+    pub fn drag_to(&mut self, point: GridPoint) {
+        if let Some(selection) = &mut self.selection {
+            selection.extend(self.terminal.selection_unit(selection.mode(), point));
+        }
+    }
+
+    /// The text copying the mouse selection would put into the clipboard.
+    pub fn selected_text(&self) -> Option<String> {
+        self.selection
+            .filter(|selection| !selection.is_empty())
+            .map(|selection| self.terminal.selection_text(&selection))
+    }
+
+    /// Enters copy mode, like the [`KeyAction::CopyMode`] binding does.
+    pub fn enter_copy_mode(&mut self) {
+        self.copy_mode = Some(CopyMode::new(self.terminal.copy_mode_start()));
+        self.selection = None;
+    }
+
+    /// The copy mode cursor, or `None` outside of copy mode.
+    pub fn copy_mode_cursor(&self) -> Option<GridPoint> {
+        self.copy_mode.as_ref().map(|copy_mode| copy_mode.cursor)
+    }
+
+    /// Simulates a key press in copy mode, `key` being the key with Shift applied.
+    ///
+    /// Returns the text copied to the clipboard if the key yanked the selection.
+    pub fn copy_mode_key(&mut self, key: Key, modifiers: Modifiers) -> Option<String> {
+        let copy_mode = self.copy_mode.as_mut()?;
+        let (action, selection) = self.terminal.copy_mode_key(copy_mode, &key, modifiers);
+
+        match action {
+            CopyModeAction::Update => {
+                let _ = self
+                    .terminal
+                    .update(Message::ScrollToRow(copy_mode.cursor.row));
+                self.selection = selection;
+                None
+            }
+            CopyModeAction::Yank | CopyModeAction::Exit => {
+                self.copy_mode = None;
+                self.selection = None;
+                let _ = self
+                    .terminal
+                    .update(Message::Action(KeyAction::ScrollToBottom));
+
+                selection
+                    .filter(|_| action == CopyModeAction::Yank)
+                    .map(|selection| self.terminal.selection_text(&selection))
+            }
+        }
+    }
+
+    /// Simulates typing the keys in copy mode, e.g. `"ggVjy"`.
+    ///
+    /// Returns the yanked text, like [`TestTerminal::copy_mode_key`].
+    pub fn copy_mode_keys(&mut self, keys: &str) -> Option<String> {
+        keys.chars().fold(None, |yanked, c| {
+            let key = Key::Character(c.to_string().into());
+            self.copy_mode_key(key, Modifiers::empty()).or(yanked)
+        })
+    }
+
+    /// Handles the clipboard requests (OSC 52) of the program like the widget does,
+    /// with `clipboard` standing in for the system clipboard.
+    ///
+    /// Returns the messages the widget would publish, e.g. the response to a query.
+    pub fn handle_clipboard_requests(&self, clipboard: &mut dyn Clipboard) -> Vec<Message> {
+        let mut messages = Vec::new();
+        self.terminal
+            .handle_clipboard_requests(clipboard, &mut Shell::new(&mut messages));
+        messages
+    }
+
+    /// The link the mouse would hover at the cell, as it would be opened on Ctrl+click.
+    pub fn link_at(&self, point: GridPoint) -> Option<Link> {
+        self.terminal.link_at(point).map(|hovered| hovered.link)
+    }
+
+    /// The text spans the widget would lay out for the current viewport.
+    pub fn spans(&self) -> Vec<iced::advanced::text::Span<'static, (), iced::Font>> {
+        self.terminal.spans()
+    }
+}
+
+fn describe(attrs: &CellAttributes) -> String {
+    let mut parts = Vec::new();
+
+    match attrs.intensity() {
+        Intensity::Normal => {}
+        Intensity::Bold => parts.push("bold".to_string()),
+        Intensity::Half => parts.push("dim".to_string()),
+    }
+    if attrs.underline() != Underline::None {
+        parts.push("underline".to_string());
+    }
+    if attrs.italic() {
+        parts.push("italic".to_string());
+    }
+    if attrs.reverse() {
+        parts.push("reverse".to_string());
+    }
+    if attrs.strikethrough() {
+        parts.push("strikethrough".to_string());
+    }
+    if attrs.invisible() {
+        parts.push("invisible".to_string());
+    }
+    if attrs.foreground() != ColorAttribute::Default {
+        parts.push(format!("fg={:?}", attrs.foreground()));
+    }
+    if attrs.background() != ColorAttribute::Default {
+        parts.push(format!("bg={:?}", attrs.background()));
+    }
+    if attrs.hyperlink().is_some() {
+        parts.push("link".to_string());
+    }
+
+    parts.join(" ")
+}
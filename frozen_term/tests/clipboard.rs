@@ -0,0 +1,117 @@
+use frozen_term::{ClipboardPolicy, ClipboardRequest, KeyAction, Message, TestTerminal};
+use iced::advanced::{Clipboard, clipboard::Kind};
+
+const WRITE: &str = "\x1b]52;c;aGk=\x07";
+const QUERY: &str = "\x1b]52;c;?\x07";
+
+/// Stands in for the system clipboard, keeping every write.
+#[derive(Default)]
+struct FakeClipboard {
+    contents: Option<String>,
+    writes: Vec<(Kind, String)>,
+}
+
+impl Clipboard for FakeClipboard {
+    fn read(&self, _kind: Kind) -> Option<String> {
+        self.contents.clone()
+    }
+
+    fn write(&mut self, kind: Kind, contents: String) {
+        self.writes.push((kind, contents));
+    }
+}
+
+fn terminal(policy: ClipboardPolicy) -> TestTerminal {
+    TestTerminal::new(2, 20).configure(|terminal| terminal.clipboard_policy(policy))
+}
+
+#[test]
+fn deny_ignores_everything() {
+    let mut term = terminal(ClipboardPolicy::Deny);
+    term.feed(WRITE);
+    term.feed(QUERY);
+
+    let mut clipboard = FakeClipboard::default();
+    assert!(term.handle_clipboard_requests(&mut clipboard).is_empty());
+    assert!(clipboard.writes.is_empty());
+}
+
+#[test]
+fn allow_write_copies_but_drops_queries() {
+    let mut term = terminal(ClipboardPolicy::AllowWrite);
+    term.feed(QUERY);
+    term.feed(WRITE);
+
+    let mut clipboard = FakeClipboard {
+        contents: Some("secret".into()),
+        ..Default::default()
+    };
+    assert!(term.handle_clipboard_requests(&mut clipboard).is_empty());
+    assert_eq!(clipboard.writes, [(Kind::Standard, "hi".to_string())]);
+}
+
+#[test]
+fn repeated_writes_collapse_to_the_latest() {
+    let mut term = terminal(ClipboardPolicy::AllowWrite);
+    term.feed("\x1b]52;c;Zmlyc3Q=\x07");
+    term.feed("\x1b]52;p;cHJpbWFyeQ==\x07");
+    term.feed(WRITE);
+
+    let mut clipboard = FakeClipboard::default();
+    term.handle_clipboard_requests(&mut clipboard);
+    assert_eq!(
+        clipboard.writes,
+        [
+            (Kind::Primary, "primary".to_string()),
+            (Kind::Standard, "hi".to_string())
+        ]
+    );
+}
+
+#[test]
+fn allow_read_write_answers_queries() {
+    let mut term = terminal(ClipboardPolicy::AllowReadWrite);
+    term.feed(QUERY);
+
+    let mut clipboard = FakeClipboard {
+        contents: Some("hi".into()),
+        ..Default::default()
+    };
+    let messages = term.handle_clipboard_requests(&mut clipboard);
+
+    let [Message::Action(KeyAction::SendBytes(response))] = messages.as_slice() else {
+        panic!("expected a response, got {messages:?}");
+    };
+    assert_eq!(response, b"\x1b]52;c;aGk=\x1b\\");
+}
+
+#[test]
+fn ask_publishes_every_request() {
+    let mut term = terminal(ClipboardPolicy::Ask);
+    term.feed(WRITE);
+    term.feed("\x1b]52;p;?\x07");
+
+    let mut clipboard = FakeClipboard::default();
+    let messages = term.handle_clipboard_requests(&mut clipboard);
+
+    assert!(clipboard.writes.is_empty());
+    let requests: Vec<_> = messages
+        .into_iter()
+        .map(|message| match message {
+            Message::ClipboardRequest(request) => request,
+            message => panic!("expected a clipboard request, got {message:?}"),
+        })
+        .collect();
+    assert_eq!(
+        requests,
+        [
+            ClipboardRequest::Write {
+                kind: Kind::Standard,
+                text: Some("hi".into())
+            },
+            ClipboardRequest::Read {
+                kind: Kind::Primary
+            },
+        ]
+    );
+}
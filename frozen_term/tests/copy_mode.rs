@@ -0,0 +1,160 @@
+use frozen_term::{GridPoint, TestTerminal};
+use iced::keyboard::{Key, Modifiers, key::Named};
+
+/// A cell on the visible screen.
+fn at(term: &TestTerminal, row: usize, col: usize) -> GridPoint {
+    GridPoint {
+        row: term.terminal().screen_rows().start + row as isize,
+        col,
+    }
+}
+
+fn press(term: &mut TestTerminal, named: Named) -> Option<String> {
+    term.copy_mode_key(Key::Named(named), Modifiers::empty())
+}
+
+#[test]
+fn starts_at_the_cursor() {
+    let mut term = TestTerminal::new(3, 20);
+    term.feed("$ ls\r\n$ ");
+    term.enter_copy_mode();
+
+    assert_eq!(term.copy_mode_cursor(), Some(at(&term, 1, 2)));
+
+    assert_eq!(term.copy_mode_keys("q"), None);
+    assert_eq!(term.copy_mode_cursor(), None);
+}
+
+#[test]
+fn words_across_wrapped_and_empty_rows() {
+    let mut term = TestTerminal::new(5, 10);
+    term.feed("foo bar\r\n\r\nbazqux123 end");
+    term.enter_copy_mode();
+    term.copy_mode_keys("gg");
+
+    let mut forward = Vec::new();
+    for _ in 0..3 {
+        term.copy_mode_keys("w");
+        forward.push(term.copy_mode_cursor().unwrap());
+    }
+    // the empty row is skipped, and the next word starts on the wrapped row
+    assert_eq!(forward, [at(&term, 0, 4), at(&term, 2, 0), at(&term, 3, 0)]);
+
+    let mut backward = Vec::new();
+    for _ in 0..4 {
+        term.copy_mode_keys("b");
+        backward.push(term.copy_mode_cursor().unwrap());
+    }
+    // `b` stays at the first cell of the scrollback
+    assert_eq!(
+        backward,
+        [
+            at(&term, 2, 0),
+            at(&term, 0, 4),
+            at(&term, 0, 0),
+            at(&term, 0, 0)
+        ]
+    );
+}
+
+#[test]
+fn first_and_last_row_and_end_of_row() {
+    let mut term = TestTerminal::new(3, 10);
+    term.feed("first\r\n\r\nthree\r\nfour\r\nend x");
+    let rows = term.terminal().all_rows();
+    term.enter_copy_mode();
+
+    // the top of the scrollback, which is above the screen
+    term.copy_mode_keys("gg");
+    assert_eq!(
+        term.copy_mode_cursor(),
+        Some(GridPoint {
+            row: rows.start,
+            col: 0
+        })
+    );
+    term.copy_mode_keys("$");
+    assert_eq!(term.copy_mode_cursor().unwrap().col, 4);
+
+    // an empty row has no last character
+    term.copy_mode_keys("j$");
+    assert_eq!(term.copy_mode_cursor().unwrap().col, 0);
+
+    term.copy_mode_keys("G");
+    assert_eq!(
+        term.copy_mode_cursor(),
+        Some(GridPoint {
+            row: rows.end - 1,
+            col: 0
+        })
+    );
+    term.copy_mode_keys("$");
+    assert_eq!(term.copy_mode_cursor().unwrap().col, 4);
+}
+
+#[test]
+fn search_is_smartcase_and_wraps_around() {
+    let mut term = TestTerminal::new(4, 20);
+    term.feed("Error one\r\nerror two\r\nERROR three\r\nerror four");
+    term.enter_copy_mode();
+
+    // lowercase queries ignore case, starting after the cursor at the end of the screen
+    term.copy_mode_keys("/error");
+    press(&mut term, Named::Enter);
+    assert_eq!(term.copy_mode_cursor(), Some(at(&term, 0, 0)));
+
+    let mut found = Vec::new();
+    for _ in 0..4 {
+        term.copy_mode_keys("n");
+        found.push(term.copy_mode_cursor().unwrap().row);
+    }
+    let row = |row| at(&term, row, 0).row;
+    assert_eq!(found, [row(1), row(2), row(3), row(0)]);
+
+    term.copy_mode_keys("N");
+    assert_eq!(term.copy_mode_cursor(), Some(at(&term, 3, 0)));
+
+    // uppercase letters make the query case-sensitive
+    term.copy_mode_keys("/ERROR");
+    press(&mut term, Named::Enter);
+    assert_eq!(term.copy_mode_cursor(), Some(at(&term, 2, 0)));
+    term.copy_mode_keys("n");
+    assert_eq!(term.copy_mode_cursor(), Some(at(&term, 2, 0)));
+    term.copy_mode_keys("N");
+    assert_eq!(term.copy_mode_cursor(), Some(at(&term, 2, 0)));
+}
+
+#[test]
+fn yanking_cells_lines_and_blocks() {
+    let mut term = TestTerminal::new(4, 10);
+    term.feed("one two\r\n0123456789abc\r\nthree");
+
+    // the cells under the anchor and the cursor are both included
+    term.enter_copy_mode();
+    assert_eq!(term.copy_mode_keys("ggwvlly").unwrap(), "two");
+    assert_eq!(term.copy_mode_cursor(), None);
+
+    // lines follow wrapped lines
+    term.enter_copy_mode();
+    assert_eq!(term.copy_mode_keys("ggjlVy").unwrap(), "0123456789abc");
+    term.enter_copy_mode();
+    term.copy_mode_keys("ggjVjj");
+    assert_eq!(
+        press(&mut term, Named::Enter).unwrap(),
+        "0123456789abc\nthree"
+    );
+
+    // the rows of a block are separated, even for a wrapped line
+    term.enter_copy_mode();
+    term.copy_mode_keys("ggl");
+    term.copy_mode_key(Key::Character("v".into()), Modifiers::CTRL);
+    assert_eq!(term.copy_mode_keys("jjlly").unwrap(), "ne\n123\nbc");
+
+    // Escape leaves visual mode first, without copying anything
+    term.enter_copy_mode();
+    term.copy_mode_keys("v");
+    assert_eq!(press(&mut term, Named::Escape), None);
+    assert!(term.copy_mode_cursor().is_some());
+    assert_eq!(press(&mut term, Named::Escape), None);
+    assert_eq!(term.copy_mode_cursor(), None);
+}
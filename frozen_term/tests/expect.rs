@@ -0,0 +1,53 @@
+use std::time::Duration;
+
+use frozen_term::{CaptureWriter, Expect, ExpectError, SearchPattern, Terminal};
+use regex::Regex;
+use tokio::io::AsyncWriteExt;
+
+const TIMEOUT: Duration = Duration::from_secs(1);
+
+#[tokio::test]
+async fn waits_for_output_and_sends_input() {
+    let input = CaptureWriter::default();
+    let terminal = Terminal::new(3, 20, Box::new(input.clone()));
+    let (mut program, output) = tokio::io::duplex(1024);
+    let mut expect = Expect::new(terminal, output);
+
+    program.write_all(b"\x1b[1mlogin:\x1b[0m ").await.unwrap();
+    let login = SearchPattern::Text("login:".into());
+    assert_eq!(
+        expect.expect_output(&login, TIMEOUT).await.unwrap(),
+        "login:"
+    );
+
+    expect.send("root\r");
+    expect.terminal_mut().wait_until_written(TIMEOUT);
+    assert_eq!(input.contents(), b"root\r");
+
+    program.write_all(b"root\r\nwelcome 42\r\n").await.unwrap();
+    let welcome = SearchPattern::Regex(Regex::new(r"welcome \d+").unwrap());
+    assert_eq!(
+        expect.expect_screen(&welcome, TIMEOUT).await.unwrap(),
+        "welcome 42"
+    );
+
+    // the prompt was consumed by the first match
+    assert!(matches!(
+        expect
+            .expect_output(&login, Duration::from_millis(50))
+            .await,
+        Err(ExpectError::Timeout)
+    ));
+
+    let huge = SearchPattern::CaseInsensitive("login".repeat(60_000));
+    assert!(matches!(
+        expect.expect_screen(&huge, TIMEOUT).await,
+        Err(ExpectError::Pattern(_))
+    ));
+
+    drop(program);
+    assert!(matches!(
+        expect.expect_output(&login, TIMEOUT).await,
+        Err(ExpectError::Eof)
+    ));
+}
@@ -0,0 +1,49 @@
+use frozen_term::{ExportFormat, TestTerminal};
+
+fn terminal() -> TestTerminal {
+    let mut term = TestTerminal::new(3, 20);
+    term.feed("plain \x1b[1;32mgreen <b>\x1b[0m\r\nsecond line");
+    term
+}
+
+#[test]
+fn plain_text() {
+    let term = terminal();
+    let rows = term.terminal().screen_rows();
+
+    assert_eq!(
+        term.terminal().export(rows, ExportFormat::PlainText),
+        "plain green <b>\nsecond line\n"
+    );
+}
+
+#[test]
+fn ansi() {
+    let term = terminal();
+    let rows = term.terminal().screen_rows();
+
+    assert_eq!(
+        term.terminal().export(rows, ExportFormat::Ansi),
+        "\x1b[0mplain \x1b[0;1;32mgreen <b>\x1b[0m\r\n\x1b[0msecond line\x1b[0m\r\n"
+    );
+}
+
+#[test]
+fn html_escapes_text() {
+    let term = terminal();
+    let rows = term.terminal().screen_rows();
+    let html = term.terminal().export(rows, ExportFormat::Html);
+
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("font-weight: bold;\">green &lt;b&gt;</span>"));
+}
+
+#[test]
+fn svg_has_a_text_per_run() {
+    let term = terminal();
+    let rows = term.terminal().screen_rows();
+    let svg = term.terminal().export(rows, ExportFormat::Svg);
+
+    assert!(svg.starts_with("<svg "));
+    assert_eq!(svg.matches("<text ").count(), 3);
+}
@@ -0,0 +1,165 @@
+use std::{io::Write, sync::mpsc, time::Duration};
+
+use frozen_term::{
+    AltEncoding, BackspaceEncoding, KeyAction, KeyBindings, KeyEncoding, KeyNotationError, Message,
+    Terminal, TestTerminal,
+};
+use iced::keyboard::{Key, Modifiers, key::Named};
+
+const TIMEOUT: Duration = Duration::from_secs(1);
+
+#[test]
+fn typed_text_is_written() {
+    let mut term = TestTerminal::new(2, 20);
+    term.type_text("ls");
+    term.key_press(Key::Named(Named::Enter), Modifiers::empty());
+
+    assert_eq!(term.take_input(TIMEOUT), b"ls\r");
+}
+
+#[test]
+fn ctrl_c_sends_etx() {
+    let mut term = TestTerminal::new(2, 20);
+    term.key_press(Key::Character("c".into()), Modifiers::CTRL);
+
+    assert_eq!(term.take_input(TIMEOUT), b"\x03");
+}
+
+#[test]
+fn backspace_encoding() {
+    let mut term = TestTerminal::new(2, 20);
+    term.key_press(Key::Named(Named::Backspace), Modifiers::empty());
+    assert_eq!(term.take_input(TIMEOUT), b"\x7f");
+
+    let mut term = TestTerminal::new(2, 20).configure(|terminal| {
+        terminal.key_encoding(KeyEncoding {
+            backspace: BackspaceEncoding::CtrlH,
+            ..KeyEncoding::default()
+        })
+    });
+    term.key_press(Key::Named(Named::Backspace), Modifiers::empty());
+    assert_eq!(term.take_input(TIMEOUT), b"\x08");
+}
+
+#[test]
+fn alt_eighth_bit() {
+    let mut term = TestTerminal::new(2, 20).configure(|terminal| {
+        terminal.key_encoding(KeyEncoding {
+            alt: AltEncoding::EighthBit,
+            ..KeyEncoding::default()
+        })
+    });
+    term.key_press(Key::Character("x".into()), Modifiers::ALT);
+
+    assert_eq!(term.take_input(TIMEOUT), [b'x' | 0x80]);
+}
+
+#[test]
+fn send_keys_parses_the_notation() {
+    let mut term = TestTerminal::new(2, 20);
+    term.terminal_mut().send_keys("ls <lt>x<CR><C-c>").unwrap();
+
+    assert_eq!(term.take_input(TIMEOUT), b"ls <x\r\x03");
+
+    assert_eq!(
+        term.terminal_mut().send_keys("a<C-Foo>"),
+        Err(KeyNotationError::UnknownKey("C-Foo".into()))
+    );
+    assert_eq!(
+        term.terminal_mut().send_keys("a<C-c"),
+        Err(KeyNotationError::Unclosed(1))
+    );
+    assert_eq!(term.take_input(Duration::from_millis(50)), b"");
+}
+
+#[test]
+fn ctrl_plus_zooms_with_a_shifted_plus() {
+    let bindings = KeyBindings::default();
+
+    for (key, modifiers) in [
+        ("=", Modifiers::CTRL),
+        ("=", Modifiers::CTRL | Modifiers::SHIFT),
+        ("+", Modifiers::CTRL),
+    ] {
+        assert_eq!(
+            bindings.find(&Key::Character(key.into()), modifiers),
+            Some(&KeyAction::IncreaseFontSize)
+        );
+    }
+}
+
+#[test]
+fn encoded_keys_keep_their_order() {
+    let mut term = TestTerminal::new(2, 20).configure(|terminal| {
+        terminal.key_encoding(KeyEncoding {
+            backspace: BackspaceEncoding::CtrlH,
+            ..KeyEncoding::default()
+        })
+    });
+    term.type_text("ab");
+    term.key_press(Key::Named(Named::Backspace), Modifiers::empty());
+    assert_eq!(term.take_input(TIMEOUT), b"ab\x08");
+
+    term.terminal_mut().send_keys("ab<BS>").unwrap();
+    assert_eq!(term.take_input(TIMEOUT), b"ab\x08");
+}
+
+#[test]
+fn sent_bytes_follow_typed_keys() {
+    let mut term = TestTerminal::new(2, 20);
+    term.type_text("ab");
+    let _ = term
+        .terminal_mut()
+        .update(Message::Action(KeyAction::SendBytes(b"\x1b[A".to_vec())));
+    term.type_text("c");
+    term.terminal_mut().send_bytes("d");
+
+    assert_eq!(term.take_input(TIMEOUT), b"ab\x1b[Acd");
+}
+
+#[test]
+fn replies_to_queries_keep_their_place() {
+    let mut term = TestTerminal::new(2, 20);
+    term.type_text("a");
+    // a device status report, which wezterm_term answers with `\x1b[0n`
+    term.feed("\x1b[5n");
+    term.type_text("b");
+    term.terminal_mut().send_bytes("c");
+
+    assert_eq!(term.take_input(TIMEOUT), b"a\x1b[0nbc");
+}
+
+#[test]
+fn sent_bytes_are_not_bracketed() {
+    let mut term = TestTerminal::new(2, 20);
+    term.feed("\x1b[?2004h");
+    term.terminal_mut().send_bytes("a");
+    let _ = term.terminal_mut().update(Message::Paste("b".into()));
+
+    assert_eq!(term.take_input(TIMEOUT), b"a\x1b[200~b\x1b[201~");
+}
+
+/// A pty of a program that doesn't read its input until told to.
+struct BlockedWriter(mpsc::Receiver<()>);
+
+impl Write for BlockedWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.recv().map_err(|_| std::io::ErrorKind::BrokenPipe)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn a_program_not_reading_its_input_does_not_block() {
+    let (_unblock, blocked) = mpsc::channel();
+    let mut terminal = Terminal::new(2, 20, Box::new(BlockedWriter(blocked)));
+
+    // returns although none of it can be written
+    terminal.send_bytes("a");
+    let _ = terminal.update(Message::Paste("b".repeat(100_000)));
+    terminal.send_keys("c<CR>").unwrap();
+}
@@ -0,0 +1,91 @@
+use frozen_term::{GridPoint, Link, TestTerminal};
+
+/// The link detected at the first character of `target` in `line`.
+fn link_at(line: &str, target: &str) -> Option<Link> {
+    let mut term = TestTerminal::new(2, 80);
+    term.feed(line);
+
+    let row = term.terminal().screen_rows().start;
+    let col = line.find(target).unwrap();
+    term.link_at(GridPoint { row, col })
+}
+
+fn file(path: &str, line: Option<usize>, column: Option<usize>) -> Option<Link> {
+    Some(Link::File {
+        path: path.into(),
+        line,
+        column,
+    })
+}
+
+#[test]
+fn urls() {
+    assert_eq!(
+        link_at("see https://example.com/a?b=1.", "https"),
+        Some(Link::Uri("https://example.com/a?b=1".into()))
+    );
+    assert_eq!(
+        link_at("(file:///tmp/report.html)", "file"),
+        Some(Link::Uri("file:///tmp/report.html".into()))
+    );
+    assert!(!matches!(
+        link_at("xhttps://example.com", "xhttps"),
+        Some(Link::Uri(_))
+    ));
+}
+
+#[test]
+fn file_positions() {
+    assert_eq!(
+        link_at("error at src/main.rs:12:5: expected", "src"),
+        file("src/main.rs", Some(12), Some(5))
+    );
+    assert_eq!(
+        link_at("  --> ./lib.rs:7", "./lib"),
+        file("./lib.rs", Some(7), None)
+    );
+    assert_eq!(link_at("took 12:30 minutes", "12"), None);
+}
+
+#[test]
+fn paths() {
+    assert_eq!(
+        link_at("cd /usr/local/bin", "/usr"),
+        file("/usr/local/bin", None, None)
+    );
+    assert_eq!(
+        link_at("edit ~/.bashrc", "~"),
+        file("~/.bashrc", None, None)
+    );
+    assert_eq!(
+        link_at("--prefix=/opt/app", "/opt"),
+        file("/opt/app", None, None)
+    );
+    assert_eq!(
+        link_at("open docs/guide.md", "docs"),
+        file("docs/guide.md", None, None)
+    );
+}
+
+#[test]
+fn slashes_inside_words_are_not_paths() {
+    assert_eq!(link_at("mounted read/write", "write"), None);
+    assert_eq!(link_at("cats and/or dogs", "or"), None);
+    assert_eq!(link_at("released 2024/10/18", "10"), None);
+}
+
+#[test]
+fn hyperlinks_win_over_detected_links() {
+    let mut term = TestTerminal::new(2, 80);
+    term.feed("\x1b]8;;https://example.com/docs\x1b\\see /usr/share/doc\x1b]8;;\x1b\\ or /etc");
+
+    let row = term.terminal().screen_rows().start;
+    assert_eq!(
+        term.link_at(GridPoint { row, col: 4 }),
+        Some(Link::Uri("https://example.com/docs".into()))
+    );
+    assert_eq!(
+        term.link_at(GridPoint { row, col: 23 }),
+        file("/etc", None, None)
+    );
+}
@@ -0,0 +1,31 @@
+use frozen_term::LogViewer;
+
+fn lines(viewer: &LogViewer) -> Vec<String> {
+    let terminal = viewer.terminal();
+    let mut lines = terminal.lines(terminal.all_rows());
+    while lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+    lines
+}
+
+#[test]
+fn bare_line_feeds_start_a_new_line() {
+    let mut viewer = LogViewer::new();
+    viewer.advance_bytes("Compiling foo\nCompiling bar\r");
+    viewer.advance_bytes("\n\x1b[32mFinished\x1b[0m\n");
+
+    assert_eq!(lines(&viewer), ["Compiling foo", "Compiling bar", "Finished"]);
+}
+
+#[test]
+fn keeps_appending_past_the_screen() {
+    let mut viewer = LogViewer::new();
+    for line in 0..1000 {
+        viewer.advance_bytes(format!("line {line}\n"));
+    }
+
+    let lines = lines(&viewer);
+    assert_eq!(lines.len(), 1000);
+    assert_eq!(lines[999], "line 999");
+}
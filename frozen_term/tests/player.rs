@@ -0,0 +1,66 @@
+use std::time::{Duration, Instant};
+
+use frozen_term::{Player, PlayerMessage};
+
+const CAST: &str = r#"{"version": 2, "width": 20, "height": 3}
+[0.5, "o", "$ echo hi\r\n"]
+[1.0, "o", "hi\r\n"]
+[11.0, "i", "exit\r"]
+[11.0, "o", "$ "]
+[12.0, "r", "30x4"]
+"#;
+
+fn screen(player: &Player) -> String {
+    let terminal = player.terminal();
+    terminal.lines(terminal.screen_rows()).join("\n")
+}
+
+#[test]
+fn seeking_replays_the_output() {
+    let mut player = Player::new(CAST).unwrap();
+    assert_eq!(player.duration(), Duration::from_secs(12));
+
+    player.seek(Duration::from_secs(11));
+    assert_eq!(screen(&player), "$ echo hi\nhi\n$");
+
+    player.seek(Duration::from_millis(600));
+    assert_eq!(screen(&player), "$ echo hi\n\n");
+
+    player.seek(Duration::from_secs(12));
+    assert_eq!(player.terminal().size().cols, 30);
+}
+
+#[test]
+fn idle_time_is_compressed() {
+    let mut player = Player::new(CAST).unwrap();
+    player.set_idle_time_limit(Some(Duration::from_secs(2)));
+
+    assert_eq!(player.duration(), Duration::from_secs(4));
+}
+
+#[test]
+fn invalid_recordings_are_rejected() {
+    assert!(Player::new("").is_err());
+    assert!(Player::new(r#"{"version": 1, "width": 20, "height": 3}"#).is_err());
+    assert!(Player::new("{\"version\": 2, \"width\": 20, \"height\": 3}\n[\"x\"]").is_err());
+}
+
+#[test]
+fn invalid_speeds_are_not_used() {
+    let mut player = Player::new(CAST).unwrap();
+    let start = Instant::now();
+    let _ = player.update(PlayerMessage::Play);
+    let _ = player.update(PlayerMessage::Tick(start));
+
+    let _ = player.update(PlayerMessage::SetSpeed(-2.0));
+    assert_eq!(player.speed(), 0.0);
+    let _ = player.update(PlayerMessage::SetSpeed(f32::NAN));
+    assert_eq!(player.speed(), 0.0);
+    let _ = player.update(PlayerMessage::Tick(start + Duration::from_secs(1)));
+    assert_eq!(player.position(), Duration::ZERO);
+
+    let _ = player.update(PlayerMessage::SetSpeed(f32::MAX));
+    let _ = player.update(PlayerMessage::Tick(start + Duration::from_secs(2)));
+    assert_eq!(player.position(), player.duration());
+    assert!(!player.is_playing());
+}
@@ -0,0 +1,35 @@
+use frozen_term::{CaptureWriter, RecordingOptions, TestTerminal};
+
+#[test]
+fn records_output_and_resizes() {
+    let buffer = CaptureWriter::default();
+    let mut term = TestTerminal::new(24, 80);
+
+    term.terminal_mut()
+        .start_recording(buffer.clone(), RecordingOptions::default())
+        .unwrap();
+    // the euro sign is split between two chunks
+    term.feed(b"price: \xe2\x82");
+    term.feed(b"\xac\r\n");
+    term.resize(30, 100);
+    term.type_text("x");
+    term.take_input(std::time::Duration::from_secs(1));
+    term.terminal_mut().stop_recording();
+    term.feed("not recorded");
+
+    assert!(!term.terminal().is_recording());
+
+    let recording = String::from_utf8(buffer.contents()).unwrap();
+    let lines: Vec<&str> = recording.lines().collect();
+
+    assert_eq!(lines.len(), 4);
+    let header: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+    assert_eq!(header["version"], 2);
+    assert_eq!(
+        (header["width"].as_u64(), header["height"].as_u64()),
+        (Some(80), Some(24))
+    );
+    assert!(lines[1].ends_with(r#","o","price: "]"#));
+    assert!(lines[2].ends_with(r#","o","€\r\n"]"#));
+    assert!(lines[3].ends_with(r#","r","100x30"]"#));
+}
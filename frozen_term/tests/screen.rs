@@ -0,0 +1,195 @@
+use std::time::Duration;
+
+use frozen_term::{KeyAction, Message, SearchPattern, TestTerminal};
+use termwiz::surface::CursorVisibility;
+
+#[test]
+fn prints_text_and_line_breaks() {
+    let mut term = TestTerminal::new(4, 20);
+    term.feed("hello\r\nworld");
+
+    assert_eq!(term.snapshot(), "hello\nworld\n\n");
+}
+
+#[test]
+fn carriage_return_overwrites_the_line() {
+    let mut term = TestTerminal::new(2, 20);
+    term.feed("progress 10%\rprogress 100%");
+
+    assert_eq!(term.snapshot(), "progress 100%\n");
+}
+
+#[test]
+fn clear_screen_and_cursor_movement() {
+    let mut term = TestTerminal::new(3, 10);
+    term.feed("garbage\r\nmore garbage");
+    term.feed("\x1b[2J\x1b[2;3Hx");
+
+    assert_eq!(term.snapshot(), "\n  x\n");
+
+    let cursor = term.terminal().cursor_position();
+    assert_eq!((cursor.x, cursor.y), (3, 1));
+}
+
+#[test]
+fn sgr_attributes() {
+    let mut term = TestTerminal::new(2, 30);
+    term.feed("\x1b[1;31merror\x1b[0m: \x1b[4mdetails\x1b[24m done");
+
+    assert_eq!(
+        term.attribute_snapshot(),
+        "<bold fg=PaletteIndex(1)>error</>: <underline>details</> done\n"
+    );
+}
+
+#[test]
+fn resize_keeps_the_text() {
+    let mut term = TestTerminal::new(3, 20);
+    term.feed("first\r\nsecond");
+    term.resize(4, 30);
+
+    let size = term.terminal().size();
+    assert_eq!((size.rows, size.cols), (4, 30));
+    assert!(term.snapshot().starts_with("first\nsecond"));
+}
+
+#[test]
+fn spans_follow_the_attributes() {
+    let mut term = TestTerminal::new(2, 10);
+    term.feed("\x1b[31mred\x1b[0m plain");
+
+    let spans = term.spans();
+    assert_eq!(spans.len(), 2);
+    assert_eq!(spans[0].text, "red");
+    assert!(spans[0].color.is_some());
+    assert!(spans[1].text.starts_with(" plain"));
+    assert_eq!(spans[1].color, None);
+}
+
+#[test]
+fn search_matches_are_highlighted() {
+    let mut term = TestTerminal::new(3, 20);
+    term.feed("one two\r\nTWO three");
+
+    let matches = term
+        .terminal_mut()
+        .search(SearchPattern::CaseInsensitive("two".into()))
+        .unwrap()
+        .to_vec();
+    assert_eq!(matches.len(), 2);
+    assert_eq!((matches[1].start.col, matches[1].end.col), (0, 3));
+
+    let highlighted: Vec<_> = term
+        .spans()
+        .into_iter()
+        .filter(|span| span.highlight.is_some())
+        .map(|span| span.text.to_string())
+        .collect();
+    assert_eq!(highlighted, ["two", "TWO"]);
+}
+
+#[test]
+fn huge_queries_are_rejected() {
+    let mut term = TestTerminal::new(3, 20);
+    term.feed("one two");
+    term.terminal_mut()
+        .search(SearchPattern::Text("two".into()))
+        .unwrap();
+
+    let huge = SearchPattern::CaseInsensitive("two".repeat(100_000));
+    assert!(term.terminal().find(&huge).is_err());
+    assert!(term.terminal_mut().search(huge).is_err());
+    assert_eq!(term.terminal().search_matches().len(), 1);
+}
+
+#[test]
+fn scrollback_is_limited_and_can_be_cleared() {
+    let mut term = TestTerminal::new(2, 10).configure(|terminal| terminal.scrollback_size(3));
+    for line in 0..10 {
+        term.feed(format!("{line}\r\n"));
+    }
+
+    let terminal = term.terminal();
+    assert_eq!(
+        terminal.lines(terminal.all_rows()),
+        ["6", "7", "8", "9", ""]
+    );
+
+    term.terminal_mut().clear_scrollback();
+    let terminal = term.terminal();
+    assert_eq!(terminal.lines(terminal.all_rows()), ["9", ""]);
+}
+
+#[test]
+fn hard_reset_clears_everything() {
+    let mut term = TestTerminal::new(2, 10);
+    term.feed("1\r\n2\r\n3\r\n\x1b[31mred");
+
+    term.terminal_mut().hard_reset();
+
+    let terminal = term.terminal();
+    assert_eq!(terminal.lines(terminal.all_rows()), ["", ""]);
+    assert!(term.take_input(Duration::from_millis(50)).is_empty());
+
+    term.feed("plain");
+    assert_eq!(term.attribute_snapshot(), "plain\n");
+}
+
+#[test]
+fn clear_screen_keeps_the_cursor_line() {
+    let mut term = TestTerminal::new(3, 10);
+    term.feed("1\r\n2\r\n3\r\n4\r\n$ ls");
+
+    let _ = term
+        .terminal_mut()
+        .update(Message::Action(KeyAction::ClearScreen));
+
+    assert_eq!(term.snapshot(), "$ ls\n\n");
+    let terminal = term.terminal();
+    assert_eq!(
+        terminal.lines(terminal.all_rows()),
+        ["1", "2", "3", "4", "$ ls", "", ""]
+    );
+    assert!(term.take_input(Duration::from_millis(50)).is_empty());
+
+    term.feed(" -l");
+    assert_eq!(term.snapshot(), "$ ls -l\n\n");
+}
+
+#[test]
+fn soft_reset_keeps_the_contents() {
+    let mut term = TestTerminal::new(2, 20);
+    term.feed("\x1b[?25l\x1b[31mred");
+
+    let _ = term
+        .terminal_mut()
+        .update(Message::Action(KeyAction::SoftReset));
+    term.feed(" plain");
+
+    assert_eq!(
+        term.attribute_snapshot(),
+        "<fg=PaletteIndex(1)>red</> plain\n"
+    );
+    assert_eq!(
+        term.terminal().cursor_position().visibility,
+        CursorVisibility::Visible
+    );
+    assert!(term.take_input(Duration::from_millis(50)).is_empty());
+}
+
+#[test]
+fn search_matches_follow_new_output() {
+    let mut term = TestTerminal::new(3, 10).configure(|terminal| terminal.scrollback_size(5));
+    let pattern = SearchPattern::Text("err".into());
+    term.terminal_mut().search(pattern.clone()).unwrap();
+
+    // long lines wrap from the scrollback into the screen
+    for line in 0..20 {
+        term.feed(format!("{line} err ok err\r\n"));
+        term.feed("er");
+        term.feed("r\r\n");
+
+        let terminal = term.terminal();
+        assert_eq!(terminal.search_matches(), terminal.find(&pattern).unwrap());
+    }
+}
@@ -0,0 +1,104 @@
+use frozen_term::{GridPoint, TestTerminal};
+use iced::keyboard::Modifiers;
+
+/// A cell boundary on the visible screen.
+fn at(term: &TestTerminal, row: usize, col: usize) -> GridPoint {
+    GridPoint {
+        row: term.terminal().screen_rows().start + row as isize,
+        col,
+    }
+}
+
+#[test]
+fn dragging_selects_cells() {
+    let mut term = TestTerminal::new(3, 20);
+    term.feed("hello world\r\nsecond line");
+
+    term.click(at(&term, 0, 6), 1, Modifiers::empty());
+    assert_eq!(term.selected_text(), None);
+
+    term.drag_to(at(&term, 1, 6));
+    assert_eq!(term.selected_text().unwrap(), "world\nsecond");
+}
+
+#[test]
+fn custom_word_chars() {
+    let line = "run ./build-all.sh --fast";
+    let double_click = |word_chars: &str| {
+        let mut term = TestTerminal::new(2, 40).configure(|terminal| {
+            terminal
+                .smart_selection_rules(Vec::new())
+                .word_chars(word_chars)
+        });
+        term.feed(line);
+        term.click(
+            at(&term, 0, line.find("all").unwrap()),
+            2,
+            Modifiers::empty(),
+        );
+        term.selected_text().unwrap()
+    };
+
+    assert_eq!(double_click("/-+\\~_."), "./build-all.sh");
+    assert_eq!(double_click("-"), "build-all");
+    assert_eq!(double_click(""), "all");
+}
+
+#[test]
+fn triple_click_follows_wrapped_lines() {
+    let mut term = TestTerminal::new(4, 10);
+    term.feed("$ echo\r\n0123456789abcde\r\n$");
+
+    // on the second row of the wrapped line
+    term.click(at(&term, 2, 2), 3, Modifiers::empty());
+    assert_eq!(term.selected_text().unwrap(), "0123456789abcde");
+
+    term.drag_to(at(&term, 3, 0));
+    assert_eq!(term.selected_text().unwrap(), "0123456789abcde\n$");
+}
+
+#[test]
+fn block_rows_are_separated_and_trimmed() {
+    let mut term = TestTerminal::new(4, 10);
+    term.feed("ab cd  x\r\nef\r\n0123456789abc");
+
+    term.click(at(&term, 0, 1), 1, Modifiers::ALT);
+    term.drag_to(at(&term, 3, 5));
+
+    // the wrapped line isn't joined, and the short row stays as an empty line
+    assert_eq!(term.selected_text().unwrap(), "b cd\nf\n1234\nbc");
+}
+
+#[test]
+fn selections_stay_on_their_text_while_scrolling() {
+    let mut term = TestTerminal::new(3, 20);
+    term.feed("first\r\nselected text\r\n");
+
+    term.click(at(&term, 1, 0), 1, Modifiers::empty());
+    term.drag_to(at(&term, 1, 8));
+    assert_eq!(term.selected_text().unwrap(), "selected");
+
+    term.feed("more\r\noutput\r\nscrolling\r\nthe screen");
+    assert!(!term.snapshot().contains("selected"));
+    assert_eq!(term.selected_text().unwrap(), "selected");
+}
+
+#[test]
+fn shift_click_extends_by_the_selection_unit() {
+    let mut term = TestTerminal::new(2, 40);
+    term.feed("alpha beta gamma delta");
+
+    term.click(at(&term, 0, 7), 2, Modifiers::empty());
+    assert_eq!(term.selected_text().unwrap(), "beta");
+
+    term.click(at(&term, 0, 13), 1, Modifiers::SHIFT);
+    assert_eq!(term.selected_text().unwrap(), "beta gamma");
+
+    // the word clicked first stays selected when extending before it
+    term.click(at(&term, 0, 2), 1, Modifiers::SHIFT);
+    assert_eq!(term.selected_text().unwrap(), "alpha beta");
+
+    // without Shift, a click starts a new selection
+    term.click(at(&term, 0, 2), 1, Modifiers::empty());
+    assert_eq!(term.selected_text(), None);
+}
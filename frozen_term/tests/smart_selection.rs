@@ -0,0 +1,37 @@
+use frozen_term::{GridPoint, TestTerminal};
+
+/// The text double-clicking the first character of `target` in `line` selects.
+fn select_at(line: &str, target: &str) -> String {
+    let mut term = TestTerminal::new(2, 80);
+    term.feed(line);
+
+    let row = term.terminal().screen_rows().start;
+    let col = line.find(target).unwrap();
+    term.word_selection_at(GridPoint { row, col })
+}
+
+#[test]
+fn urls_win_over_paths() {
+    assert_eq!(
+        select_at("see https://example.com/docs/index.html now", "docs"),
+        "https://example.com/docs/index.html"
+    );
+}
+
+#[test]
+fn paths_and_addresses() {
+    assert_eq!(select_at("cat /etc/hosts", "hosts"), "/etc/hosts");
+    assert_eq!(select_at("mounted read/write", "write"), "read/write");
+    assert_eq!(
+        select_at("listening on 192.168.0.10:8080.", "168"),
+        "192.168.0.10:8080"
+    );
+    assert_eq!(select_at("HEAD is now at 3f2a9c1e fix", "9c1e"), "3f2a9c1e");
+}
+
+#[test]
+fn quotes_but_not_apostrophes() {
+    assert_eq!(select_at(r#"name = "hello world""#, "world"), "hello world");
+    assert_eq!(select_at("echo 'single quoted'", "quoted"), "single quoted");
+    assert_eq!(select_at("don't stop, it's fine", "stop"), "stop");
+}